@@ -0,0 +1,19 @@
+use std::time::Duration;
+
+// The engine advances in fixed frames at this rate, so the same inputs play
+// out the same way on every machine
+pub const FPS: u32 = 60;
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+
+// How much of `amount` goes by each frame when all of it takes `interval`,
+// rounded down
+pub(crate) fn per_frame(amount: u32, interval: Duration) -> u64 {
+    let per_frame = amount as u128 * NANOS_PER_SECOND / (FPS as u128 * interval.as_nanos().max(1));
+    per_frame.min(u64::MAX as u128) as u64
+}
+
+// How long `frames` frames last
+pub fn to_duration(frames: u64) -> Duration {
+    Duration::from_nanos((frames as u128 * NANOS_PER_SECOND / FPS as u128) as u64)
+}
@@ -0,0 +1,41 @@
+use std::ops::{Add, AddAssign, Sub};
+
+// A cell on the board, or an offset between two cells. Rows grow downwards
+// from the top of the buffer, so anything above the board has a negative row.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Coord {
+    pub row: i32,
+    pub col: i32,
+}
+
+impl Coord {
+    pub const LEFT: Coord = Coord { row: 0, col: -1 };
+    pub const RIGHT: Coord = Coord { row: 0, col: 1 };
+    pub const DOWN: Coord = Coord { row: 1, col: 0 };
+
+    pub const fn new(row: i32, col: i32) -> Coord {
+        Coord { row, col }
+    }
+}
+
+impl Add for Coord {
+    type Output = Coord;
+
+    fn add(self, other: Coord) -> Coord {
+        Coord::new(self.row + other.row, self.col + other.col)
+    }
+}
+
+impl AddAssign for Coord {
+    fn add_assign(&mut self, other: Coord) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Coord {
+    type Output = Coord;
+
+    fn sub(self, other: Coord) -> Coord {
+        Coord::new(self.row - other.row, self.col - other.col)
+    }
+}
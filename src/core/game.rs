@@ -0,0 +1,486 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::clock;
+
+use super::coord::Coord;
+use super::gravity::{Fall, Gravity};
+use super::grid::Grid;
+use super::level::Level;
+use super::lock::LockDelay;
+use super::piece::{Piece, Rotation, Tetrimonos};
+use super::queue::PieceQueue;
+use super::rules::Rules;
+use super::stats::Stats;
+use super::scoring::{Award, Clear, Scoring};
+use super::tspin::{self, Spin};
+
+// Actions a player (or a bot) can feed into the engine
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Input {
+    Left,
+    Right,
+    // Shift as far as possible, for zero ARR
+    SlideLeft,
+    SlideRight,
+    RotateClockwise,
+    RotateCounterClockwise,
+    Rotate180,
+    SoftDrop,
+    // Drops onto the shadow and locks straight away
+    HardDrop,
+    // Drops onto the shadow but leaves the piece free to move
+    SonicDrop,
+    Hold,
+}
+
+// Why the game ended
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TopOut {
+    // A new piece spawned overlapping the stack
+    BlockOut,
+    // A piece locked entirely above the skyline, in the hidden buffer
+    LockOut,
+    // Incoming garbage pushed blocks off the top of the buffer
+    GarbageOut,
+}
+
+// What the engine is doing, a piece only being in play while falling
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Phase {
+    Falling,
+    // Full rows stay on the board for `remaining` frames before they are
+    // removed
+    LineClear { remaining: u32 },
+    // Waiting `remaining` frames for the next piece to spawn (ARE)
+    Entry { remaining: u32 },
+}
+
+// Things worth announcing that happened during a step
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Event {
+    // A piece locked and cleared lines, spun, or both
+    Clear { clear: Clear, award: Award },
+}
+
+pub struct Tetris {
+    score: i32,
+    total_lines: i32,
+    level: Level,
+    grid: Grid,
+    piece: Piece,
+    phase: Phase,
+    seed: u64,
+    rng: StdRng,
+    rules: Rules,
+    queue: PieceQueue,
+    held: Option<Tetrimonos>,
+    // Only one hold is allowed until the current piece locks
+    hold_used: bool,
+    fall: Fall,
+    lock_delay: LockDelay,
+    scoring: Scoring,
+    stats: Stats,
+    events: Vec<Event>,
+    frames: u64,
+    paused: bool,
+    top_out: Option<TopOut>,
+}
+
+impl Tetris {
+    pub fn new() -> Tetris {
+        Tetris::with_seed(rand::thread_rng().gen())
+    }
+
+    // The same seed always deals the same sequence of pieces
+    pub fn with_seed(seed: u64) -> Tetris {
+        Tetris::with_rules(seed, Rules::default())
+    }
+
+    pub fn with_rules(seed: u64, rules: Rules) -> Tetris {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut queue = PieceQueue::new(rules.randomizer.create(), rules.preview);
+        let grid = Grid::new(rules.height, rules.width, rules.buffer);
+        let piece = Tetris::deal(&grid, &rules, &mut queue, &mut rng);
+        let mut lock_delay = LockDelay::new(rules.lock_delay, rules.lock_reset);
+        lock_delay.restart(piece.position().row);
+        let mut tetris = Tetris {
+            score: 0,
+            total_lines: 0,
+            level: Level::new(),
+            grid,
+            piece,
+            phase: Phase::Falling,
+            seed,
+            rng,
+            rules,
+            queue,
+            held: None,
+            hold_used: false,
+            fall: Fall::default(),
+            lock_delay,
+            scoring: Scoring::new(),
+            stats: Stats::default(),
+            events: Vec::new(),
+            frames: 0,
+            paused: false,
+            top_out: None,
+        };
+        tetris.apply_instant_gravity();
+        tetris
+    }
+
+    pub fn score(&self) -> i32 {
+        self.score
+    }
+
+    pub fn total_lines(&self) -> i32 {
+        self.total_lines
+    }
+
+    pub fn level(&self) -> &Level {
+        &self.level
+    }
+
+    // Current speed of the rules' gravity curve
+    pub fn gravity(&self) -> Gravity {
+        self.rules.gravity.gravity(&self.level)
+    }
+
+    // The piece in play, if any. There is none during line clear and entry
+    // delays.
+    pub fn piece(&self) -> Option<&Piece> {
+        match self.phase {
+            Phase::Falling => Some(&self.piece),
+            _ => None,
+        }
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    // Where the piece in play would land
+    pub fn shadow_position(&self) -> Option<Coord> {
+        self.piece().map(|piece| piece.shadow_position(&self.grid))
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    // The tetrimonos that will be dealt next, in order
+    pub fn upcoming(&self) -> &VecDeque<Tetrimonos> {
+        self.queue.upcoming()
+    }
+
+    pub fn held(&self) -> Option<Tetrimonos> {
+        self.held
+    }
+
+    pub fn can_hold(&self) -> bool {
+        self.rules.hold && !self.hold_used
+    }
+
+    pub fn lock_delay(&self) -> &LockDelay {
+        &self.lock_delay
+    }
+
+    pub fn scoring(&self) -> &Scoring {
+        &self.scoring
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    // Hands over the events that happened since the last call
+    pub fn drain_events(&mut self) -> Vec<Event> {
+        self.events.drain(..).collect()
+    }
+
+    // Frames played, which stop counting when the game ends
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn time(&self) -> Duration {
+        clock::to_duration(self.frames)
+    }
+
+    // While paused, steps are ignored so gravity, lock delay and the clock
+    // all stand still
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn top_out(&self) -> Option<TopOut> {
+        self.top_out
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.top_out.is_some()
+    }
+
+    // Raises the board by `lines` garbage rows with a gap at column `hole`,
+    // carrying the active piece up with it
+    pub fn add_garbage(&mut self, lines: usize, hole: usize) {
+        if self.is_over() {
+            return;
+        }
+        let overflowed = self.grid.push_garbage(lines, hole);
+        self.piece.raise(lines);
+        let falling = self.phase == Phase::Falling;
+        if overflowed || (falling && self.piece.is_blocked(&self.grid)) {
+            self.top_out = Some(TopOut::GarbageOut);
+        }
+    }
+
+    // Plays one frame: applies the inputs in order, then gravity and lock
+    // delay. Inputs are ignored while no piece is in play, and a hard drop
+    // ends the frame so nothing meant for it reaches the next piece.
+    pub fn step(&mut self, inputs: &[Input]) {
+        if self.is_over() || self.paused {
+            return;
+        }
+        self.frames += 1;
+        if self.phase != Phase::Falling {
+            self.wait();
+            return;
+        }
+        for input in inputs {
+            let grounded = self.piece.is_grounded(&self.grid);
+            let moved = match input {
+                Input::Left => self.piece.shift(&self.grid, Coord::LEFT),
+                Input::Right => self.piece.shift(&self.grid, Coord::RIGHT),
+                Input::SlideLeft => self.slide(Coord::LEFT),
+                Input::SlideRight => self.slide(Coord::RIGHT),
+                Input::RotateClockwise => self.piece.rotate(&self.grid, Rotation::Clockwise),
+                Input::RotateCounterClockwise => self.piece.rotate(&self.grid, Rotation::CounterClockwise),
+                Input::Rotate180 => self.piece.rotate(&self.grid, Rotation::Half),
+                Input::SoftDrop => {
+                    self.piece.shift(&self.grid, Coord::DOWN);
+                    false
+                }
+                Input::HardDrop => {
+                    self.hard_drop();
+                    return;
+                }
+                Input::SonicDrop => {
+                    self.piece.drop_to_shadow(&self.grid);
+                    false
+                }
+                Input::Hold => {
+                    self.hold();
+                    false
+                }
+            };
+            if self.is_over() || self.phase != Phase::Falling {
+                return;
+            }
+            if moved {
+                self.lock_delay.moved(grounded);
+            }
+            self.apply_instant_gravity();
+            self.lock_delay.descended(self.piece.position().row);
+        }
+        let cells = self.fall.advance(self.gravity());
+        self.tick(cells);
+        if self.lock_delay.advance(self.piece.is_grounded(&self.grid)) {
+            self.lock();
+        }
+    }
+
+    // Gravity only pulls the piece down by up to `cells`, locking is left to
+    // the lock delay
+    fn tick(&mut self, cells: u32) {
+        for _ in 0..cells {
+            if !self.piece.shift(&self.grid, Coord::DOWN) {
+                break;
+            }
+        }
+        self.lock_delay.descended(self.piece.position().row);
+    }
+
+    // At 20G the piece in play never shows in mid-air
+    fn apply_instant_gravity(&mut self) {
+        if self.gravity().is_instant() {
+            self.piece.drop_to_shadow(&self.grid);
+            self.lock_delay.descended(self.piece.position().row);
+        }
+    }
+
+    // Returns whether the piece moved at all
+    fn slide(&mut self, dir: Coord) -> bool {
+        let mut moved = false;
+        while self.piece.shift(&self.grid, dir) {
+            moved = true;
+        }
+        moved
+    }
+
+    // Hard drops are worth two points for every cell fallen
+    fn hard_drop(&mut self) {
+        let cells = self.piece.drop_to_shadow(&self.grid);
+        self.score += 2 * cells;
+        self.lock();
+    }
+
+    // Full rows are scored straight away but only removed once the line
+    // clear delay is over
+    fn lock(&mut self) {
+        let skyline = self.grid.skyline() as i32;
+        let blocks = self.piece.blocks_at(self.piece.shadow_position(&self.grid));
+        let locked_out = blocks.iter().all(|cell| cell.row < skyline);
+        let spin = tspin::detect(&self.piece, &self.grid);
+        self.grid.place(&blocks, self.piece.tetrimono());
+        let rows_reduced = self.grid.full_rows();
+        let clear = Clear {
+            lines: rows_reduced,
+            spin,
+            perfect: rows_reduced > 0 && self.grid.clears_perfectly(),
+        };
+        let award = self.scoring.award(&clear, self.level.number);
+        self.score += award.points;
+        self.stats.record_spin(spin, rows_reduced);
+        if clear.perfect {
+            self.stats.perfect_clears += 1;
+        }
+        if rows_reduced > 0 || spin != Spin::None {
+            self.events.push(Event::Clear { clear, award });
+        }
+        self.total_lines += rows_reduced as i32;
+        self.level.number = (self.total_lines / 10) + 1;
+        if locked_out && rows_reduced == 0 {
+            self.top_out = Some(TopOut::LockOut);
+            return;
+        }
+        self.phase = if rows_reduced > 0 {
+            Phase::LineClear { remaining: self.rules.line_clear_delay }
+        } else {
+            Phase::Entry { remaining: self.rules.are }
+        };
+        self.settle();
+    }
+
+    // Counts a frame off the current delay
+    fn wait(&mut self) {
+        self.phase = match self.phase {
+            Phase::Falling => Phase::Falling,
+            Phase::LineClear { remaining } =>
+                Phase::LineClear { remaining: remaining.saturating_sub(1) },
+            Phase::Entry { remaining } =>
+                Phase::Entry { remaining: remaining.saturating_sub(1) },
+        };
+        self.settle();
+    }
+
+    // Moves on from every delay that has run out, so delays of zero take no
+    // time at all
+    fn settle(&mut self) {
+        loop {
+            match self.phase {
+                Phase::LineClear { remaining: 0 } => {
+                    self.grid.clean_rows();
+                    self.phase = Phase::Entry { remaining: self.rules.are };
+                }
+                Phase::Entry { remaining: 0 } => {
+                    self.spawn_next();
+                    self.phase = Phase::Falling;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    // The freshly spawned piece has nowhere to go
+    fn check_block_out(&mut self) {
+        if self.piece.is_blocked(&self.grid) {
+            self.top_out = Some(TopOut::BlockOut);
+        }
+    }
+
+    // Swaps the current piece with the held one, dealing a fresh piece if the
+    // slot was empty. The piece coming out starts over as if just spawned.
+    fn hold(&mut self) {
+        if !self.can_hold() {
+            return;
+        }
+        let current = self.piece.tetrimono();
+        self.piece = match self.held {
+            Some(t) => Tetris::spawn(&self.grid, t, &self.rules, &mut self.rng),
+            None => Tetris::deal(&self.grid, &self.rules, &mut self.queue, &mut self.rng),
+        };
+        self.held = Some(current);
+        self.hold_used = true;
+        self.fall.reset();
+        self.lock_delay.restart(self.piece.position().row);
+        self.check_block_out();
+        self.apply_instant_gravity();
+    }
+
+    fn deal(grid: &Grid, rules: &Rules, queue: &mut PieceQueue, rng: &mut StdRng) -> Piece {
+        let t = queue.next(rng);
+        Tetris::spawn(grid, t, rules, rng)
+    }
+
+    fn spawn(grid: &Grid, t: Tetrimonos, rules: &Rules, rng: &mut StdRng) -> Piece {
+        let state = if rules.chaos {rng.gen_range(0, 4)} else {rules.spawn_state};
+        Piece::new(grid, t, state, rules.spawn_row)
+    }
+
+    fn spawn_next(&mut self) {
+        self.piece = Tetris::deal(&self.grid, &self.rules, &mut self.queue, &mut self.rng);
+        self.hold_used = false;
+        self.fall.reset();
+        self.lock_delay.restart(self.piece.position().row);
+        self.check_block_out();
+        self.apply_instant_gravity();
+    }
+}
+
+impl Default for Tetris {
+    fn default() -> Tetris {
+        Tetris::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Pieces in play and the preview after each hard drop until the game ends
+    fn deals(seed: u64) -> Vec<(Tetrimonos, Vec<Tetrimonos>)> {
+        let mut tetris = Tetris::with_seed(seed);
+        let mut deals = Vec::new();
+        while !tetris.is_over() {
+            let piece = tetris.piece().map(|p| p.tetrimono()).unwrap_or(Tetrimonos::BLANK);
+            deals.push((piece, tetris.upcoming().iter().cloned().collect()));
+            tetris.step(&[Input::HardDrop]);
+        }
+        deals
+    }
+
+    #[test]
+    fn same_seed_deals_same_pieces() {
+        for seed in [0, 1, 42, u64::MAX].iter() {
+            let first = deals(*seed);
+            assert!(first.len() > 1);
+            assert_eq!(first, deals(*seed));
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::clock;
+use super::level::Level;
+
+// One G is a cell per frame at 60 frames per second. Gravity is kept in
+// steps of 1/65536 G so slow and fractional speeds stay exact, and speeds
+// given in frames per cell keep their period exactly.
+pub const G: u32 = 65536;
+
+// Pieces fall straight to the floor at this speed or above
+pub const MAX_GRAVITY: u32 = 20 * G;
+
+// NES frames per cell, for levels 0 to 9
+const NES_FRAMES: [u32; 10] = [48, 43, 38, 33, 28, 23, 18, 13, 8, 6];
+
+// TGM internal gravity in 1/256 G, from each TGM level on
+const TGM_GRAVITY: [(i32, u32); 30] = [
+    (0, 4), (30, 6), (35, 8), (40, 10), (50, 12), (60, 16), (70, 32),
+    (80, 48), (90, 64), (100, 80), (120, 96), (140, 112), (160, 128),
+    (170, 144), (200, 4), (220, 32), (230, 64), (233, 96), (236, 128),
+    (239, 160), (243, 192), (247, 224), (251, 256), (300, 512), (330, 768),
+    (360, 1024), (400, 1280), (420, 1024), (450, 768), (500, 5120),
+];
+
+// TGM levels run up to 999 and rise with every piece. Ours rise every ten
+// lines, so each of ours stands in for this many of theirs.
+const TGM_LEVELS_PER_LEVEL: i32 = 50;
+
+// How fast pieces fall
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Gravity {
+    // In 1/65536 of a cell per `frames` frames
+    amount: u32,
+    frames: u32,
+}
+
+impl Gravity {
+    pub fn new(per_frame: u32) -> Gravity {
+        Gravity {
+            amount: per_frame.clamp(1, MAX_GRAVITY),
+            frames: 1,
+        }
+    }
+
+    // One cell every `frames` frames
+    pub fn from_frames(frames: u32) -> Gravity {
+        Gravity {
+            amount: G,
+            frames: frames.max(1),
+        }
+    }
+
+    // One cell every `interval`
+    pub fn from_interval(interval: Duration) -> Gravity {
+        Gravity::new(clock::per_frame(G, interval).min(MAX_GRAVITY as u64) as u32)
+    }
+
+    // In 1/65536 of a cell, rounded up
+    pub fn per_frame(self) -> u32 {
+        self.amount.div_ceil(self.frames)
+    }
+
+    // Cells per frame, for showing
+    pub fn g(self) -> f32 {
+        self.amount as f32 / (G as f32 * self.frames as f32)
+    }
+
+    // 20G, where pieces never show in mid-air
+    pub fn is_instant(self) -> bool {
+        self.per_frame() >= MAX_GRAVITY
+    }
+}
+
+// Turns frames into whole cells fallen, carrying the fraction of a cell over
+// to the next frame
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Fall {
+    // In 1/65536 of a cell, times the gravity's `frames`
+    progress: u64,
+}
+
+impl Fall {
+    // Cells to fall this frame
+    pub(crate) fn advance(&mut self, gravity: Gravity) -> u32 {
+        let cell = G as u64 * gravity.frames as u64;
+        self.progress += gravity.amount as u64;
+        let cells = self.progress / cell;
+        self.progress %= cell;
+        cells as u32
+    }
+
+    // A new piece starts falling from scratch
+    pub(crate) fn reset(&mut self) {
+        self.progress = 0;
+    }
+}
+
+// How gravity grows with the level
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum GravityCurve {
+    // The original millisecond table in `Level::get_speed`
+    #[default]
+    Table,
+    // (0.8 - (level - 1) * 0.007) ^ (level - 1) seconds per cell
+    Guideline,
+    // NES frames per cell, level 1 being NES level 0
+    Nes,
+    // TGM internal gravity, reaching 20G from level 11
+    Tgm,
+    // 20G from the start
+    Instant,
+}
+
+impl GravityCurve {
+    pub fn gravity(self, level: &Level) -> Gravity {
+        let number = level.number.max(1);
+        match self {
+            GravityCurve::Table => Gravity::from_interval(Duration::from_millis(level.get_speed())),
+            GravityCurve::Guideline => {
+                // The formula stops making sense past level 20, by which
+                // point it is well beyond 20G anyway
+                let n = number.min(20) - 1;
+                let seconds = (0.8 - n as f64 * 0.007).powi(n);
+                Gravity::from_interval(Duration::from_secs_f64(seconds))
+            }
+            GravityCurve::Nes => {
+                let frames = match number - 1 {
+                    nes @ 0..=9 => NES_FRAMES[nes as usize],
+                    10..=12 => 5,
+                    13..=15 => 4,
+                    16..=18 => 3,
+                    19..=28 => 2,
+                    _ => 1,
+                };
+                Gravity::from_frames(frames)
+            }
+            GravityCurve::Tgm => {
+                let tgm_level = (number - 1) * TGM_LEVELS_PER_LEVEL;
+                let internal = TGM_GRAVITY.iter().rev()
+                    .find(|(from, _)| tgm_level >= *from)
+                    .map_or(4, |(_, internal)| *internal);
+                Gravity::new(internal * (G / 256))
+            }
+            GravityCurve::Instant => Gravity::new(MAX_GRAVITY),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GravityCurve::Table => "Table",
+            GravityCurve::Guideline => "Guideline",
+            GravityCurve::Nes => "NES",
+            GravityCurve::Tgm => "TGM",
+            GravityCurve::Instant => "20G",
+        }
+    }
+}
+
+impl fmt::Display for GravityCurve {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for GravityCurve {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<GravityCurve, String> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(GravityCurve::Table),
+            "guideline" => Ok(GravityCurve::Guideline),
+            "nes" => Ok(GravityCurve::Nes),
+            "tgm" => Ok(GravityCurve::Tgm),
+            "20g" | "instant" => Ok(GravityCurve::Instant),
+            _ => Err(format!("unknown gravity curve: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Frames, counting from 1, on which the first `cells` cells fall
+    fn fall_frames(gravity: Gravity, cells: usize) -> Vec<u32> {
+        let mut fall = Fall::default();
+        let mut frames = Vec::new();
+        let mut frame = 0;
+        while frames.len() < cells {
+            frame += 1;
+            for _ in 0..fall.advance(gravity) {
+                frames.push(frame);
+            }
+        }
+        frames.truncate(cells);
+        frames
+    }
+
+    #[test]
+    fn nes_levels_fall_on_their_listed_frames() {
+        let mut expected: Vec<(i32, u32)> = NES_FRAMES.iter().enumerate()
+            .map(|(i, frames)| (i as i32 + 1, *frames))
+            .collect();
+        expected.extend_from_slice(&[(11, 5), (14, 4), (17, 3), (20, 2), (30, 1)]);
+        for (level, frames) in expected {
+            let gravity = GravityCurve::Nes.gravity(&Level { number: level });
+            // Taller than any board a piece could fall through
+            let listed: Vec<u32> = (1..=64).map(|cell| cell * frames).collect();
+            assert_eq!(fall_frames(gravity, 64), listed, "level {}", level);
+        }
+    }
+}
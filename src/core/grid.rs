@@ -0,0 +1,256 @@
+use super::coord::Coord;
+use super::piece::Tetrimonos;
+
+// Rows and columns of a standard board
+pub const DEFAULT_SIZE: (usize, usize) = (20, 10);
+
+// Smallest board every tetrimono still fits on in any orientation
+pub const MIN_SIZE: (usize, usize) = (4, 4);
+
+// Every column gets a bit of a row's mask
+pub const MAX_WIDTH: usize = 32;
+
+// Hidden rows above the visible field that pieces spawn into
+pub const DEFAULT_BUFFER: usize = 20;
+
+// Pieces spawn in the two rows above the skyline. Boards with a smaller
+// buffer give up their top visible rows for it instead.
+const MIN_SKYLINE: usize = 2;
+
+// Rows are indexed from the top of the buffer, the visible field starts at
+// row `buffer`. Occupancy is kept as one bitmask per row, with bit `col` set
+// for a filled column, so collision and line clear checks never allocate.
+// Which tetrimono filled a cell is only needed for drawing and lives in a
+// separate colour layer.
+#[derive(Clone, PartialEq)]
+pub struct Grid {
+    masks: Vec<u32>,
+    colors: Vec<Tetrimonos>,
+    height: usize,
+    width: usize,
+    buffer: usize,
+}
+
+impl Grid {
+    pub fn new(height: usize, width: usize, buffer: usize) -> Grid {
+        let height = height.max(MIN_SIZE.0);
+        let width = width.max(MIN_SIZE.1).min(MAX_WIDTH);
+        Grid {
+            masks: vec![0; buffer + height],
+            colors: vec![Tetrimonos::BLANK; (buffer + height) * width],
+            height,
+            width,
+            buffer,
+        }
+    }
+
+    // Visible rows only
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // Hidden rows above the visible ones
+    pub fn buffer(&self) -> usize {
+        self.buffer
+    }
+
+    // All rows, hidden and visible
+    pub fn rows(&self) -> usize {
+        self.buffer + self.height
+    }
+
+    // First row below the area pieces spawn and top out in
+    pub fn skyline(&self) -> usize {
+        self.buffer.max(MIN_SKYLINE)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn cell(&self, row: usize, col: usize) -> Tetrimonos {
+        self.colors[row * self.width + col]
+    }
+
+    // Occupancy of a whole row, bit `col` standing for column `col`
+    pub fn row_mask(&self, row: usize) -> u32 {
+        self.masks[row]
+    }
+
+    fn full_mask(&self) -> u32 {
+        u32::MAX >> (MAX_WIDTH - self.width)
+    }
+
+    pub fn is_row_full(&self, row: usize) -> bool {
+        self.masks[row] == self.full_mask()
+    }
+
+    pub fn full_rows(&self) -> usize {
+        (0..self.rows()).filter(|row| self.is_row_full(*row)).count()
+    }
+
+    // Removing the full rows would leave nothing behind
+    pub fn clears_perfectly(&self) -> bool {
+        self.masks.iter().all(|mask| *mask == 0 || *mask == self.full_mask())
+    }
+
+    // Cells outside the grid count as occupied
+    pub fn is_occupied(&self, cell: Coord) -> bool {
+        cell.row < 0 || cell.row >= self.rows() as i32 || cell.col < 0 || cell.col >= self.width as i32 ||
+            self.masks[cell.row as usize] & (1 << cell.col) != 0
+    }
+
+    pub fn collides(&self, cells: &[Coord]) -> bool {
+        cells.iter().any(|cell| self.is_occupied(*cell))
+    }
+
+    // How many rows `cells` can fall before landing on something
+    pub fn drop_distance(&self, cells: &[Coord]) -> i32 {
+        let mut distance = 0;
+        while !cells.iter().any(|cell| self.is_occupied(*cell + Coord::new(distance + 1, 0))) {
+            distance += 1;
+        }
+        distance
+    }
+
+    // Fills `cells` with `t`, which must all be on the board
+    pub(crate) fn place(&mut self, cells: &[Coord], t: Tetrimonos) {
+        for cell in cells {
+            self.masks[cell.row as usize] |= 1 << cell.col;
+            self.colors[cell.row as usize * self.width + cell.col as usize] = t;
+        }
+    }
+
+    // Pushes `lines` garbage rows in from the bottom with a gap at column
+    // `hole`, or the last column if `hole` is past it, so that garbage can
+    // never be cleared without the player's help. Returns whether any blocks
+    // were pushed off the top of the buffer.
+    pub fn push_garbage(&mut self, lines: usize, hole: usize) -> bool {
+        let (rows, width) = (self.rows(), self.width);
+        let lines = lines.min(rows);
+        let overflowed = self.masks[..lines].iter().any(|mask| *mask != 0);
+        self.masks.copy_within(lines.., 0);
+        self.colors.copy_within(lines * width.., 0);
+        let mask = self.full_mask() & !(1 << hole.min(width - 1));
+        for row in rows - lines..rows {
+            self.masks[row] = mask;
+            for col in 0..width {
+                self.colors[row * width + col] =
+                    if mask & (1 << col) != 0 {Tetrimonos::GARBAGE}
+                    else {Tetrimonos::BLANK};
+            }
+        }
+        overflowed
+    }
+
+    // Removes every full row, moving the rows above down in place
+    // returns number of rows removed
+    pub fn clean_rows(&mut self) -> usize {
+        let (full, width) = (self.full_mask(), self.width);
+        // Walk up from the bottom, moving each row that stays down so that
+        // everything from `top` on is kept
+        let mut top = self.rows();
+        for row in (0..self.rows()).rev() {
+            if self.masks[row] == full {
+                continue;
+            }
+            top -= 1;
+            if top != row {
+                self.masks[top] = self.masks[row];
+                self.colors.copy_within(row * width..(row + 1) * width, top * width);
+            }
+        }
+        // One empty row comes in on top for every row removed
+        for mask in self.masks[..top].iter_mut() {
+            *mask = 0;
+        }
+        for color in self.colors[..top * width].iter_mut() {
+            *color = Tetrimonos::BLANK;
+        }
+        top
+    }
+}
+
+impl Default for Grid {
+    fn default() -> Grid {
+        Grid::new(DEFAULT_SIZE.0, DEFAULT_SIZE.1, DEFAULT_BUFFER)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill_row(grid: &mut Grid, row: usize, t: Tetrimonos) {
+        let cells: Vec<Coord> = (0..grid.width()).map(|col| Coord::new(row as i32, col as i32)).collect();
+        grid.place(&cells, t);
+    }
+
+    #[test]
+    fn clean_rows_moves_colours_with_their_rows() {
+        let mut grid = Grid::new(6, 4, 0);
+        fill_row(&mut grid, 5, Tetrimonos::I);
+        grid.place(&[Coord::new(4, 0)], Tetrimonos::S);
+        fill_row(&mut grid, 3, Tetrimonos::J);
+        grid.place(&[Coord::new(2, 1), Coord::new(2, 2)], Tetrimonos::T);
+        assert_eq!(grid.full_rows(), 2);
+        assert_eq!(grid.clean_rows(), 2);
+        assert_eq!(grid.row_mask(5), 0b0001);
+        assert_eq!(grid.cell(5, 0), Tetrimonos::S);
+        assert_eq!(grid.cell(5, 1), Tetrimonos::BLANK);
+        assert_eq!(grid.row_mask(4), 0b0110);
+        assert_eq!(grid.cell(4, 1), Tetrimonos::T);
+        assert_eq!(grid.cell(4, 2), Tetrimonos::T);
+        assert_eq!(grid.cell(4, 0), Tetrimonos::BLANK);
+        for row in 0..4 {
+            assert_eq!(grid.row_mask(row), 0);
+            for col in 0..4 {
+                assert_eq!(grid.cell(row, col), Tetrimonos::BLANK);
+            }
+        }
+    }
+
+    #[test]
+    fn full_rows_on_widest_board() {
+        let mut grid = Grid::new(4, MAX_WIDTH, 0);
+        assert_eq!(grid.width(), MAX_WIDTH);
+        fill_row(&mut grid, 3, Tetrimonos::L);
+        let almost: Vec<Coord> = (1..MAX_WIDTH).map(|col| Coord::new(2, col as i32)).collect();
+        grid.place(&almost, Tetrimonos::O);
+        assert!(grid.is_row_full(3));
+        assert!(!grid.is_row_full(2));
+        assert!(grid.is_occupied(Coord::new(3, MAX_WIDTH as i32 - 1)));
+        assert!(grid.is_occupied(Coord::new(3, MAX_WIDTH as i32)));
+        assert_eq!(grid.clean_rows(), 1);
+        assert_eq!(grid.row_mask(3), !1);
+        assert_eq!(grid.cell(3, MAX_WIDTH - 1), Tetrimonos::O);
+    }
+
+    #[test]
+    fn push_garbage_reports_overflow_past_the_buffer() {
+        let mut grid = Grid::new(4, 4, 2);
+        grid.place(&[Coord::new(1, 3)], Tetrimonos::Z);
+        // The block only reaches the top row
+        assert!(!grid.push_garbage(1, 0));
+        assert_eq!(grid.row_mask(0), 0b1000);
+        assert_eq!(grid.cell(0, 3), Tetrimonos::Z);
+        assert_eq!(grid.row_mask(5), 0b1110);
+        assert_eq!(grid.cell(5, 0), Tetrimonos::BLANK);
+        assert_eq!(grid.cell(5, 1), Tetrimonos::GARBAGE);
+        // Now it goes off the top
+        assert!(grid.push_garbage(1, 2));
+        assert_eq!(grid.row_mask(4), 0b1110);
+        assert_eq!(grid.row_mask(5), 0b1011);
+        // The hole stays on the board
+        assert!(!grid.push_garbage(1, 99));
+        assert_eq!(grid.row_mask(5), 0b0111);
+        assert!(!grid.is_row_full(5));
+        // More lines than the grid has rows replace all of it
+        assert!(grid.push_garbage(10, 1));
+        for row in 0..grid.rows() {
+            assert_eq!(grid.row_mask(row), 0b1101);
+            assert_eq!(grid.cell(row, 1), Tetrimonos::BLANK);
+            assert_eq!(grid.cell(row, 3), Tetrimonos::GARBAGE);
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use super::game::Input;
+use super::gravity::{Fall, Gravity};
+
+// How held keys repeat, as chosen by the player
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Handling {
+    // Delayed Auto Shift: frames a direction is held before it repeats
+    pub das: u32,
+    // Auto Repeat Rate: frames between repeated shifts, zero slides to the
+    // wall
+    pub arr: u32,
+    // How many times faster than gravity a held soft drop falls
+    pub soft_drop_factor: u32,
+}
+
+impl Default for Handling {
+    fn default() -> Handling {
+        Handling {
+            das: 10,
+            arr: 2,
+            soft_drop_factor: 20,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Direction {
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn shift(self) -> Input {
+        match self {
+            Direction::Left => Input::Left,
+            Direction::Right => Input::Right,
+        }
+    }
+
+    fn slide(self) -> Input {
+        match self {
+            Direction::Left => Input::SlideLeft,
+            Direction::Right => Input::SlideRight,
+        }
+    }
+}
+
+// Turns key presses and releases into the inputs the engine understands,
+// repeating held directions and soft drop according to the `Handling`
+pub struct Controller {
+    handling: Handling,
+    left_held: bool,
+    right_held: bool,
+    // The most recently pressed direction still held
+    active: Option<Direction>,
+    das_timer: u32,
+    charged: bool,
+    arr_timer: u32,
+    soft_drop_held: bool,
+    soft_drop_fall: Fall,
+    pending: Vec<Input>,
+}
+
+impl Controller {
+    pub fn new(handling: Handling) -> Controller {
+        Controller {
+            handling,
+            left_held: false,
+            right_held: false,
+            active: None,
+            das_timer: 0,
+            charged: false,
+            arr_timer: 0,
+            soft_drop_held: false,
+            soft_drop_fall: Fall::default(),
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn handling(&self) -> &Handling {
+        &self.handling
+    }
+
+    pub fn press(&mut self, input: Input) {
+        match input {
+            Input::Left => {
+                self.left_held = true;
+                self.start(Direction::Left);
+            }
+            Input::Right => {
+                self.right_held = true;
+                self.start(Direction::Right);
+            }
+            Input::SoftDrop => {
+                self.soft_drop_held = true;
+                self.soft_drop_fall.reset();
+                self.pending.push(Input::SoftDrop);
+            }
+            _ => self.pending.push(input),
+        }
+    }
+
+    pub fn release(&mut self, input: Input) {
+        match input {
+            Input::Left => {
+                self.left_held = false;
+                if self.active == Some(Direction::Left) {
+                    self.fall_back(self.right_held, Direction::Right);
+                }
+            }
+            Input::Right => {
+                self.right_held = false;
+                if self.active == Some(Direction::Right) {
+                    self.fall_back(self.left_held, Direction::Left);
+                }
+            }
+            Input::SoftDrop => self.soft_drop_held = false,
+            _ => (),
+        }
+    }
+
+    // Forgets every held key, e.g. when the game loses focus
+    pub fn release_all(&mut self) {
+        self.left_held = false;
+        self.right_held = false;
+        self.active = None;
+        self.soft_drop_held = false;
+        self.pending.clear();
+    }
+
+    // Collects the inputs for the next frame. `gravity` is the current
+    // gravity, which the soft drop factor multiplies.
+    pub fn update(&mut self, gravity: Gravity) -> Vec<Input> {
+        let mut inputs: Vec<Input> = self.pending.drain(..).collect();
+        if let Some(direction) = self.active {
+            self.repeat(direction, &mut inputs);
+        }
+        if self.soft_drop_held {
+            let factor = self.handling.soft_drop_factor.max(1);
+            let speed = Gravity::new(gravity.per_frame().saturating_mul(factor));
+            if speed.is_instant() {
+                inputs.push(Input::SonicDrop);
+            } else {
+                for _ in 0..self.soft_drop_fall.advance(speed) {
+                    inputs.push(Input::SoftDrop);
+                }
+            }
+        }
+        inputs
+    }
+
+    // A fresh press shifts once straight away and starts charging DAS
+    fn start(&mut self, direction: Direction) {
+        self.active = Some(direction);
+        self.das_timer = 0;
+        self.charged = false;
+        self.pending.push(direction.shift());
+    }
+
+    // Releasing the active direction hands over to the opposite one if it is
+    // still held, which has to charge its own DAS
+    fn fall_back(&mut self, other_held: bool, other: Direction) {
+        if other_held {
+            self.active = Some(other);
+            self.das_timer = 0;
+            self.charged = false;
+        } else {
+            self.active = None;
+        }
+    }
+
+    fn repeat(&mut self, direction: Direction, inputs: &mut Vec<Input>) {
+        if !self.charged {
+            // The frame the key went down on is not held yet, DAS runs out
+            // `das` frames after it
+            self.das_timer += 1;
+            if self.das_timer <= self.handling.das {
+                return;
+            }
+            self.charged = true;
+            self.arr_timer = self.das_timer - self.handling.das - 1;
+            inputs.push(direction.shift());
+        } else {
+            self.arr_timer += 1;
+        }
+        if self.handling.arr == 0 {
+            inputs.push(direction.slide());
+            return;
+        }
+        while self.arr_timer >= self.handling.arr {
+            self.arr_timer -= self.handling.arr;
+            inputs.push(direction.shift());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Each shift from holding left from frame 0 on, with the frame it came on
+    fn held_left(das: u32, arr: u32, frames: usize) -> Vec<(usize, Input)> {
+        let mut controller = Controller::new(Handling { das, arr, soft_drop_factor: 20 });
+        controller.press(Input::Left);
+        (0..frames)
+            .flat_map(|frame| controller.update(Gravity::from_frames(60)).into_iter().map(move |input| (frame, input)))
+            .collect()
+    }
+
+    fn shifts(frames: &[usize]) -> Vec<(usize, Input)> {
+        frames.iter().map(|frame| (*frame, Input::Left)).collect()
+    }
+
+    #[test]
+    fn das_and_arr_repeat_on_the_right_frames() {
+        assert_eq!(held_left(10, 2, 17), shifts(&[0, 10, 12, 14, 16]));
+        assert_eq!(held_left(1, 1, 4), shifts(&[0, 1, 2, 3]));
+        assert_eq!(held_left(1, 3, 8), shifts(&[0, 1, 4, 7]));
+        assert_eq!(held_left(6, 0, 8), vec![
+            (0, Input::Left),
+            (6, Input::Left),
+            (6, Input::SlideLeft),
+            (7, Input::SlideLeft),
+        ]);
+    }
+
+    #[test]
+    fn tap_shifts_once() {
+        let mut controller = Controller::new(Handling { das: 1, arr: 0, soft_drop_factor: 20 });
+        controller.press(Input::Left);
+        assert_eq!(controller.update(Gravity::from_frames(60)), vec![Input::Left]);
+        controller.release(Input::Left);
+        assert!(controller.update(Gravity::from_frames(60)).is_empty());
+    }
+}
@@ -0,0 +1,38 @@
+pub struct Level {
+    pub number: i32,
+}
+
+impl Level {
+    pub fn new() -> Level {
+        Level {
+            number: 1,
+        }
+    }
+
+    pub fn get_speed(&self) -> u64 {
+        // Gets the milliseconds between each single-grid drop
+        match self.number {
+            1 => 750,
+            2 => 670,
+            3 => 590,
+            4 => 520,
+            5 => 440,
+            6 => 360,
+            7 => 280,
+            8 => 200,
+            9 => 125,
+            10 => 90,
+            11..=13 => 80,
+            14..=16 => 60,
+            17..=19 => 45,
+            20..=30 => 30,
+            _ => 20
+        }
+    }
+}
+
+impl Default for Level {
+    fn default() -> Level {
+        Level::new()
+    }
+}
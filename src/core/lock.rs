@@ -0,0 +1,189 @@
+use std::fmt;
+use std::str::FromStr;
+
+// Number of moves that may reset the lock timer under `LockReset::Move`
+pub const MOVE_RESET_LIMIT: usize = 15;
+
+// What restarts the lock timer of a grounded piece
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LockReset {
+    // Every successful move or rotation
+    Infinite,
+    // Moves and rotations, up to `MOVE_RESET_LIMIT` times per row reached
+    Move,
+    // Only falling to a row the piece has not reached before
+    Step,
+}
+
+impl fmt::Display for LockReset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            LockReset::Infinite => "Infinite",
+            LockReset::Move => "Move",
+            LockReset::Step => "Step",
+        })
+    }
+}
+
+impl FromStr for LockReset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<LockReset, String> {
+        match s.to_lowercase().as_str() {
+            "infinite" => Ok(LockReset::Infinite),
+            "move" => Ok(LockReset::Move),
+            "step" => Ok(LockReset::Step),
+            _ => Err(format!("unknown lock reset: {}", s)),
+        }
+    }
+}
+
+// Counts down how long a grounded piece may still be moved before it locks
+pub struct LockDelay {
+    // In frames
+    delay: u32,
+    reset: LockReset,
+    elapsed: u32,
+    moves: usize,
+    lowest: i32,
+}
+
+impl LockDelay {
+    pub fn new(delay: u32, reset: LockReset) -> LockDelay {
+        LockDelay {
+            delay,
+            reset,
+            elapsed: 0,
+            moves: 0,
+            lowest: 0,
+        }
+    }
+
+    pub fn delay(&self) -> u32 {
+        self.delay
+    }
+
+    pub fn reset(&self) -> LockReset {
+        self.reset
+    }
+
+    // Frames spent on the ground since the last reset
+    pub fn elapsed(&self) -> u32 {
+        self.elapsed
+    }
+
+    // Resets used so far under `LockReset::Move`
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    // Starts over for a piece spawned at `row`
+    pub(crate) fn restart(&mut self, row: i32) {
+        self.elapsed = 0;
+        self.moves = 0;
+        self.lowest = row;
+    }
+
+    // Called after a successful move or rotation
+    pub(crate) fn moved(&mut self, grounded: bool) {
+        if !grounded {
+            return;
+        }
+        match self.reset {
+            LockReset::Infinite => self.elapsed = 0,
+            LockReset::Move => {
+                if self.moves < MOVE_RESET_LIMIT {
+                    self.moves += 1;
+                    self.elapsed = 0;
+                }
+            }
+            LockReset::Step => (),
+        }
+    }
+
+    // Reaching a new lowest row always gives the piece a fresh timer
+    pub(crate) fn descended(&mut self, row: i32) {
+        if row > self.lowest {
+            self.lowest = row;
+            self.elapsed = 0;
+            self.moves = 0;
+        }
+    }
+
+    // Counts a frame, returning whether the piece should lock now
+    pub(crate) fn advance(&mut self, grounded: bool) -> bool {
+        if !grounded {
+            return false;
+        }
+        self.elapsed += 1;
+        self.elapsed >= self.delay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Spends all but the last frame of the delay on the ground
+    fn almost_lock(lock_delay: &mut LockDelay) {
+        for _ in 1..lock_delay.delay() {
+            assert!(!lock_delay.advance(true));
+        }
+    }
+
+    #[test]
+    fn step_reset_ignores_moves() {
+        let mut lock_delay = LockDelay::new(30, LockReset::Step);
+        lock_delay.restart(0);
+        almost_lock(&mut lock_delay);
+        lock_delay.moved(true);
+        assert_eq!(lock_delay.moves(), 0);
+        assert!(lock_delay.advance(true));
+    }
+
+    #[test]
+    fn move_reset_runs_out_until_a_new_row() {
+        let mut lock_delay = LockDelay::new(30, LockReset::Move);
+        lock_delay.restart(0);
+        for _ in 0..MOVE_RESET_LIMIT {
+            almost_lock(&mut lock_delay);
+            lock_delay.moved(true);
+        }
+        assert_eq!(lock_delay.moves(), MOVE_RESET_LIMIT);
+        almost_lock(&mut lock_delay);
+        lock_delay.moved(true);
+        assert_eq!(lock_delay.elapsed(), 29);
+        // Falling a row gives every reset back
+        lock_delay.descended(1);
+        assert_eq!((lock_delay.elapsed(), lock_delay.moves()), (0, 0));
+        almost_lock(&mut lock_delay);
+        lock_delay.moved(true);
+        assert_eq!(lock_delay.moves(), 1);
+        // A row already reached does not
+        lock_delay.descended(1);
+        assert_eq!(lock_delay.moves(), 1);
+        almost_lock(&mut lock_delay);
+        assert!(lock_delay.advance(true));
+    }
+
+    #[test]
+    fn infinite_reset_never_locks_while_moving() {
+        let mut lock_delay = LockDelay::new(30, LockReset::Infinite);
+        lock_delay.restart(0);
+        for _ in 0..100 {
+            almost_lock(&mut lock_delay);
+            lock_delay.moved(true);
+        }
+        almost_lock(&mut lock_delay);
+        assert!(lock_delay.advance(true));
+    }
+
+    #[test]
+    fn airborne_pieces_never_lock() {
+        let mut lock_delay = LockDelay::new(1, LockReset::Step);
+        lock_delay.restart(0);
+        assert!(!lock_delay.advance(false));
+        assert_eq!(lock_delay.elapsed(), 0);
+        assert!(lock_delay.advance(true));
+    }
+}
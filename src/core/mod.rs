@@ -0,0 +1,39 @@
+//! The windowless game engine.
+//!
+//! Nothing in here knows about ggez: the engine is advanced one frame at a
+//! time by calling `Tetris::step` with the inputs gathered for that frame,
+//! which makes it usable from bots, tests and replays as well as from the
+//! windowed frontend. Every delay is counted in frames of `FPS`.
+
+mod coord;
+mod clock;
+mod game;
+mod gravity;
+mod grid;
+mod handling;
+mod level;
+mod lock;
+mod piece;
+mod queue;
+mod randomizer;
+mod rules;
+mod scoring;
+mod srs;
+mod stats;
+mod tspin;
+
+pub use self::coord::Coord;
+pub use self::clock::{to_duration, FPS};
+pub use self::game::{Event, Input, Phase, Tetris, TopOut};
+pub use self::gravity::{Gravity, GravityCurve, G, MAX_GRAVITY};
+pub use self::grid::{Grid, DEFAULT_BUFFER, DEFAULT_SIZE, MAX_WIDTH, MIN_SIZE};
+pub use self::handling::{Controller, Handling};
+pub use self::level::Level;
+pub use self::lock::{LockDelay, LockReset, MOVE_RESET_LIMIT};
+pub use self::piece::{Piece, Rotation, Tetrimonos};
+pub use self::queue::{PieceQueue, MAX_PREVIEW};
+pub use self::randomizer::{Bag, History, Nes, Randomizer, RandomizerKind, Uniform};
+pub use self::rules::Rules;
+pub use self::scoring::{Award, Clear, Scoring};
+pub use self::stats::Stats;
+pub use self::tspin::Spin;
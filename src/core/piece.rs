@@ -0,0 +1,242 @@
+use num_enum::TryFromPrimitive;
+
+use super::coord::Coord;
+use super::grid::Grid;
+use super::srs;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Debug, TryFromPrimitive, PartialEq)]
+#[repr(i32)]
+pub enum Tetrimonos {
+    I,
+    O,
+    T,
+    S,
+    Z,
+    J,
+    L,
+    BLANK,
+    // Rows pushed up from below the board
+    GARBAGE,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Rotation {
+    Clockwise,
+    CounterClockwise,
+    Half,
+}
+
+impl Rotation {
+    // Number of clockwise quarter turns the rotation amounts to
+    fn turns(self) -> usize {
+        match self {
+            Rotation::Clockwise => 1,
+            Rotation::Half => 2,
+            Rotation::CounterClockwise => 3,
+        }
+    }
+}
+
+pub struct Piece {
+    tetrimono: Tetrimonos,
+    positions: [[(i32, i32); 4]; 4], // Represents relative positions of all blocks in all states
+    position: Coord, // relative to origin
+    state: usize,
+    // Turn and kick used by the last rotation, if the last thing the piece
+    // did was rotate
+    last_kick: Option<(Rotation, usize)>,
+}
+
+impl Piece {
+    // Spawns in `state` with the bottom of the piece `spawn_row` rows above
+    // the skyline, counting the first row above it as 1
+    pub fn new(grid: &Grid, t: Tetrimonos, state: usize, spawn_row: usize) -> Piece {
+        let state = state % 4;
+        let shape = Piece::shape(t, state);
+        let top = shape.iter().map(|c| c.row).min().unwrap_or(0);
+        let bottom = shape.iter().map(|c| c.row).max().unwrap_or(0);
+        let left = shape.iter().map(|c| c.col).min().unwrap_or(0);
+        let right = shape.iter().map(|c| c.col).max().unwrap_or(0);
+        // Never above the top of the buffer
+        let row = (grid.skyline() as i32 - spawn_row as i32 - bottom).max(-top);
+        // Centred, rounding left on boards where it cannot be exact
+        let width = right - left + 1;
+        let column = (grid.width() as i32 - width) / 2 - left;
+        let mut p = Piece {
+            positions: Piece::generate_positions(&t),
+            position: Coord::new(row, column),
+            // represents the state of rotation
+            state,
+            last_kick: None,
+            tetrimono: t,
+        };
+        // Pieces spawned in the buffer drop one row straight away, as in the
+        // guideline. From the default spawn row that brings their bottom into
+        // view, from higher ones they stay hidden until gravity pulls them.
+        if grid.buffer() > 0 && !p.is_blocked(grid) {
+            p.shift(grid, Coord::DOWN);
+        }
+        p
+    }
+
+    pub fn tetrimono(&self) -> Tetrimonos {
+        self.tetrimono
+    }
+
+    pub fn position(&self) -> Coord {
+        self.position
+    }
+
+    // Where the piece would land if dropped onto `grid`
+    pub fn shadow_position(&self, grid: &Grid) -> Coord {
+        self.position + Coord::new(grid.drop_distance(&self.blocks_at(self.position)), 0)
+    }
+
+    pub fn state(&self) -> usize {
+        self.state
+    }
+
+    pub fn last_kick(&self) -> Option<(Rotation, usize)> {
+        self.last_kick
+    }
+
+    // Relative blocks of a tetrimono in rotation `state`, for drawing
+    // pieces that are not on the grid
+    pub fn shape(t: Tetrimonos, state: usize) -> [Coord; 4] {
+        Piece::offset(&Piece::generate_positions(&t)[state % 4], Coord::default())
+    }
+
+    // Cells occupied by the piece with its origin at `origin`
+    pub fn blocks_at(&self, origin: Coord) -> [Coord; 4] {
+        Piece::offset(&self.positions[self.state], origin)
+    }
+
+    fn offset(blocks: &[(i32, i32); 4], origin: Coord) -> [Coord; 4] {
+        let mut cells = [origin; 4];
+        for (cell, (i, j)) in cells.iter_mut().zip(blocks.iter()) {
+            *cell += Coord::new(*i, *j);
+        }
+        cells
+    }
+
+    // SRS states as (row, col) offsets around the rotation centre, starting from the
+    // guideline spawn orientation and turning clockwise
+    fn generate_positions(t: &Tetrimonos) -> [[(i32, i32); 4]; 4] {
+        match t {
+            Tetrimonos::I => // States defined in a 4x4 box with the origin at its (1,1) cell
+                [
+                    [(0, -1), (0, 0), (0, 1), (0, 2)], // Initial state
+                    [(-1, 1), (0, 1), (1, 1), (2, 1)], // After 1 right rotation
+                    [(1, -1), (1, 0), (1, 1), (1, 2)], // 180 degrees
+                    [(-1, 0), (0, 0), (1, 0), (2, 0)], // final rotation
+                ],
+            Tetrimonos::O =>
+                [
+                    [(-1, 0), (-1, 1), (0, 0), (0, 1)],
+                    [(-1, 0), (-1, 1), (0, 0), (0, 1)],
+                    [(-1, 0), (-1, 1), (0, 0), (0, 1)],
+                    [(-1, 0), (-1, 1), (0, 0), (0, 1)],
+                ],
+            Tetrimonos::T =>
+                [
+                    [(-1, 0), (0, -1), (0, 0), (0, 1)],
+                    [(-1, 0), (0, 0), (0, 1), (1, 0)],
+                    [(0, -1), (0, 0), (0, 1), (1, 0)],
+                    [(-1, 0), (0, -1), (0, 0), (1, 0)],
+                ],
+            Tetrimonos::S =>
+                [
+                    [(-1, 0), (-1, 1), (0, -1), (0, 0)],
+                    [(-1, 0), (0, 0), (0, 1), (1, 1)],
+                    [(0, 0), (0, 1), (1, -1), (1, 0)],
+                    [(-1, -1), (0, -1), (0, 0), (1, 0)],
+                ],
+            Tetrimonos::Z =>
+                [
+                    [(-1, -1), (-1, 0), (0, 0), (0, 1)],
+                    [(-1, 1), (0, 0), (0, 1), (1, 0)],
+                    [(0, -1), (0, 0), (1, 0), (1, 1)],
+                    [(-1, 0), (0, -1), (0, 0), (1, -1)],
+                ],
+            Tetrimonos::J =>
+                [
+                    [(-1, -1), (0, -1), (0, 0), (0, 1)],
+                    [(-1, 0), (-1, 1), (0, 0), (1, 0)],
+                    [(0, -1), (0, 0), (0, 1), (1, 1)],
+                    [(-1, 0), (0, 0), (1, -1), (1, 0)],
+                ],
+            Tetrimonos::L =>
+                [
+                    [(-1, 1), (0, -1), (0, 0), (0, 1)],
+                    [(-1, 0), (0, 0), (1, 0), (1, 1)],
+                    [(0, -1), (0, 0), (0, 1), (1, -1)],
+                    [(-1, -1), (-1, 0), (0, 0), (1, 0)],
+                ],
+            Tetrimonos::BLANK | Tetrimonos::GARBAGE =>
+                [
+                    [(0, 0), (0, 0), (0, 0), (0, 0)],
+                    [(0, 0), (0, 0), (0, 0), (0, 0)],
+                    [(0, 0), (0, 0), (0, 0), (0, 0)],
+                    [(0, 0), (0, 0), (0, 0), (0, 0)],
+                ],
+        }
+    }
+
+    pub fn collides_with(&self, grid: &Grid, origin: Coord, state: usize) -> bool {
+        grid.collides(&Piece::offset(&self.positions[state], origin))
+    }
+
+    // Returns whether the rotation succeeded
+    pub fn rotate(&mut self, grid: &Grid, rotation: Rotation) -> bool {
+        let prospective_state = (self.state + rotation.turns()) % 4;
+        // Tries each SRS kick for the turn in order and keeps the first that fits
+        for (kick, (dx, dy)) in srs::kicks(self.tetrimono, self.state, prospective_state).iter().enumerate() {
+            // Kick tables treat up as positive y, the grid grows downwards
+            let kicked = self.position + Coord::new(-dy, *dx);
+            if !self.collides_with(grid, kicked, prospective_state) {
+                self.position = kicked;
+                self.state = prospective_state;
+                self.last_kick = Some((rotation, kick));
+                return true;
+            }
+        }
+        false
+    }
+
+    // Moves straight onto the shadow, returning how many cells were fallen
+    pub fn drop_to_shadow(&mut self, grid: &Grid) -> i32 {
+        let shadow = self.shadow_position(grid);
+        let cells = shadow.row - self.position.row;
+        if cells > 0 {
+            self.last_kick = None;
+        }
+        self.position = shadow;
+        cells
+    }
+
+    // Returns whether the piece could move in that direction
+    pub fn shift(&mut self, grid: &Grid, dir: Coord) -> bool {
+        if !self.collides_with(grid, self.position + dir, self.state) {
+            self.position += dir;
+            self.last_kick = None;
+            return true;
+        }
+        false
+    }
+
+    // Overlapping blocks where it stands, e.g. when it spawned on the stack
+    pub fn is_blocked(&self, grid: &Grid) -> bool {
+        self.collides_with(grid, self.position, self.state)
+    }
+
+    // Moves up by `rows` after the board underneath it was raised
+    pub(crate) fn raise(&mut self, rows: usize) {
+        self.position.row -= rows as i32;
+    }
+
+    // Resting on the stack or the floor
+    pub fn is_grounded(&self, grid: &Grid) -> bool {
+        self.collides_with(grid, self.position + Coord::DOWN, self.state)
+    }
+}
@@ -0,0 +1,37 @@
+use rand::rngs::StdRng;
+use std::collections::VecDeque;
+
+use super::piece::Tetrimonos;
+use super::randomizer::Randomizer;
+
+pub const MAX_PREVIEW: usize = 6;
+
+// Upcoming tetrimonos, generated ahead of time so they can be previewed
+pub struct PieceQueue {
+    randomizer: Box<dyn Randomizer>,
+    upcoming: VecDeque<Tetrimonos>,
+    preview: usize,
+}
+
+impl PieceQueue {
+    pub fn new(randomizer: Box<dyn Randomizer>, preview: usize) -> PieceQueue {
+        let preview = preview.min(MAX_PREVIEW);
+        PieceQueue {
+            randomizer,
+            upcoming: VecDeque::with_capacity(preview + 1),
+            preview,
+        }
+    }
+
+    // Takes the next tetrimono and tops the preview back up
+    pub fn next(&mut self, rng: &mut StdRng) -> Tetrimonos {
+        while self.upcoming.len() <= self.preview {
+            self.upcoming.push_back(self.randomizer.next(rng));
+        }
+        self.upcoming.pop_front().unwrap_or(Tetrimonos::BLANK)
+    }
+
+    pub fn upcoming(&self) -> &VecDeque<Tetrimonos> {
+        &self.upcoming
+    }
+}
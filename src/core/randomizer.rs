@@ -0,0 +1,215 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use super::piece::Tetrimonos;
+
+const ALL_TETRIMONOS: [Tetrimonos; 7] = [
+    Tetrimonos::I,
+    Tetrimonos::O,
+    Tetrimonos::T,
+    Tetrimonos::S,
+    Tetrimonos::Z,
+    Tetrimonos::J,
+    Tetrimonos::L,
+];
+
+fn random_tetrimono(rng: &mut StdRng) -> Tetrimonos {
+    match Tetrimonos::try_from(rng.gen_range(0, 7)) {
+        Ok(tetrimonos) => tetrimonos,
+        Err(_) => Tetrimonos::BLANK
+    }
+}
+
+// Decides which tetrimono comes next. All randomness must come from the
+// game's RNG so that a seed reproduces the whole sequence.
+pub trait Randomizer {
+    fn next(&mut self, rng: &mut StdRng) -> Tetrimonos;
+}
+
+// Identifies a randomizer so it can be chosen per game, shown and recorded
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum RandomizerKind {
+    #[default]
+    Bag7,
+    Bag14,
+    History4,
+    Nes,
+    Random,
+}
+
+impl RandomizerKind {
+    pub fn create(self) -> Box<dyn Randomizer> {
+        match self {
+            RandomizerKind::Bag7 => Box::new(Bag::new(1)),
+            RandomizerKind::Bag14 => Box::new(Bag::new(2)),
+            RandomizerKind::History4 => Box::new(History::new(4)),
+            RandomizerKind::Nes => Box::new(Nes::new()),
+            RandomizerKind::Random => Box::new(Uniform),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RandomizerKind::Bag7 => "7-bag",
+            RandomizerKind::Bag14 => "14-bag",
+            RandomizerKind::History4 => "TGM",
+            RandomizerKind::Nes => "NES",
+            RandomizerKind::Random => "Random",
+        }
+    }
+}
+
+impl fmt::Display for RandomizerKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for RandomizerKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<RandomizerKind, String> {
+        match s.to_lowercase().as_str() {
+            "7-bag" | "bag7" => Ok(RandomizerKind::Bag7),
+            "14-bag" | "bag14" => Ok(RandomizerKind::Bag14),
+            "tgm" | "history4" => Ok(RandomizerKind::History4),
+            "nes" => Ok(RandomizerKind::Nes),
+            "random" => Ok(RandomizerKind::Random),
+            _ => Err(format!("unknown randomizer: {}", s)),
+        }
+    }
+}
+
+// Deals every tetrimono `copies` times in a shuffled bag before refilling
+pub struct Bag {
+    copies: usize,
+    bag: Vec<Tetrimonos>,
+}
+
+impl Bag {
+    pub fn new(copies: usize) -> Bag {
+        Bag {
+            copies,
+            bag: Vec::with_capacity(copies * ALL_TETRIMONOS.len()),
+        }
+    }
+}
+
+impl Randomizer for Bag {
+    fn next(&mut self, rng: &mut StdRng) -> Tetrimonos {
+        if self.bag.is_empty() {
+            for _ in 0..self.copies {
+                self.bag.extend_from_slice(&ALL_TETRIMONOS);
+            }
+            self.bag.shuffle(rng);
+        }
+        self.bag.pop().unwrap_or(Tetrimonos::BLANK)
+    }
+}
+
+// TGM style: draws up to `tries` times in all, stopping at the first piece
+// not in the history of the last four, and never opens with an S, Z or O
+pub struct History {
+    tries: usize,
+    history: VecDeque<Tetrimonos>,
+    first: bool,
+}
+
+impl History {
+    pub fn new(tries: usize) -> History {
+        History {
+            tries,
+            history: vec![Tetrimonos::Z; 4].into(),
+            first: true,
+        }
+    }
+}
+
+impl Randomizer for History {
+    fn next(&mut self, rng: &mut StdRng) -> Tetrimonos {
+        let mut t = random_tetrimono(rng);
+        if self.first {
+            while t == Tetrimonos::S || t == Tetrimonos::Z || t == Tetrimonos::O {
+                t = random_tetrimono(rng);
+            }
+            self.first = false;
+        } else {
+            for _ in 1..self.tries {
+                if !self.history.contains(&t) {
+                    break;
+                }
+                t = random_tetrimono(rng);
+            }
+        }
+        self.history.pop_front();
+        self.history.push_back(t);
+        t
+    }
+}
+
+// NES style: rolls one of eight outcomes and rerolls once on a repeat or on
+// the dummy eighth value
+pub struct Nes {
+    last: Tetrimonos,
+}
+
+impl Nes {
+    pub fn new() -> Nes {
+        Nes {
+            last: Tetrimonos::BLANK,
+        }
+    }
+}
+
+impl Default for Nes {
+    fn default() -> Nes {
+        Nes::new()
+    }
+}
+
+impl Randomizer for Nes {
+    fn next(&mut self, rng: &mut StdRng) -> Tetrimonos {
+        let roll = Tetrimonos::try_from(rng.gen_range(0, 8)).unwrap_or(Tetrimonos::BLANK);
+        let t = if roll == Tetrimonos::BLANK || roll == self.last {
+            random_tetrimono(rng)
+        } else {
+            roll
+        };
+        self.last = t;
+        t
+    }
+}
+
+// Every tetrimono is equally likely on every draw
+pub struct Uniform;
+
+impl Randomizer for Uniform {
+    fn next(&mut self, rng: &mut StdRng) -> Tetrimonos {
+        random_tetrimono(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn bag7_deals_each_tetrimono_once_per_bag() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut bag = Bag::new(1);
+        for _ in 0..100 {
+            let mut dealt: Vec<Tetrimonos> = (0..7).map(|_| bag.next(&mut rng)).collect();
+            for t in ALL_TETRIMONOS.iter() {
+                let i = dealt.iter().position(|d| d == t).expect("tetrimono missing from bag");
+                dealt.remove(i);
+            }
+            assert!(dealt.is_empty());
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use super::gravity::GravityCurve;
+use super::grid::{DEFAULT_BUFFER, DEFAULT_SIZE};
+use super::lock::LockReset;
+use super::randomizer::RandomizerKind;
+
+// Per-game options chosen before the game starts
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rules {
+    // Board size in cells, no smaller than `MIN_SIZE` and no wider than
+    // `MAX_WIDTH`
+    pub height: usize,
+    pub width: usize,
+    // Hidden rows above the board
+    pub buffer: usize,
+    pub randomizer: RandomizerKind,
+    // How gravity speeds up with the level
+    pub gravity: GravityCurve,
+    // Classic modes play without a hold slot
+    pub hold: bool,
+    // How many upcoming pieces are shown, up to `MAX_PREVIEW`
+    pub preview: usize,
+    // Frames a grounded piece may still move before locking
+    pub lock_delay: u32,
+    pub lock_reset: LockReset,
+    // Frames cleared rows stay on the board before the stack falls
+    pub line_clear_delay: u32,
+    // Entry delay in frames between a piece locking and the next spawning
+    pub are: u32,
+    // Rotation state pieces spawn in, the guideline's flat side down being 0
+    pub spawn_state: usize,
+    // Row above the skyline the bottom of a new piece starts in, from 1
+    pub spawn_row: usize,
+    // Spawn every piece in a random orientation instead
+    pub chaos: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            height: DEFAULT_SIZE.0,
+            width: DEFAULT_SIZE.1,
+            buffer: DEFAULT_BUFFER,
+            randomizer: RandomizerKind::default(),
+            gravity: GravityCurve::default(),
+            hold: true,
+            preview: 5,
+            lock_delay: 30,
+            lock_reset: LockReset::Move,
+            line_clear_delay: 0,
+            are: 0,
+            spawn_state: 0,
+            spawn_row: 1,
+            chaos: false,
+        }
+    }
+}
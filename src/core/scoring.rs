@@ -0,0 +1,173 @@
+use super::tspin::Spin;
+
+// Points per level for a clear, indexed by kind of spin then lines cleared
+const CLEAR_POINTS: [[i32; 5]; 3] = [
+    [0, 100, 300, 500, 800],   // No spin
+    [100, 200, 400, 0, 0],     // T-spin mini
+    [400, 800, 1200, 1600, 0], // T-spin
+];
+
+// Points per level added for emptying the board, by lines cleared
+const PERFECT_CLEAR_POINTS: [i32; 5] = [0, 800, 1200, 1800, 2000];
+const BACK_TO_BACK_PERFECT_TETRIS_POINTS: i32 = 3200;
+
+// Points per level for each step of a combo
+const COMBO_POINTS: i32 = 50;
+
+// Difficult clears in a row are worth half as much again
+const BACK_TO_BACK_NUMERATOR: i32 = 3;
+const BACK_TO_BACK_DENOMINATOR: i32 = 2;
+
+// What a single locked piece achieved
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Clear {
+    pub lines: usize,
+    pub spin: Spin,
+    // The board was left completely empty
+    pub perfect: bool,
+}
+
+impl Clear {
+    // Tetrises and spins that clear lines keep a back-to-back chain going
+    pub fn is_difficult(&self) -> bool {
+        self.lines == 4 || (self.lines > 0 && self.spin != Spin::None)
+    }
+}
+
+// What a clear was awarded, for announcing it
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Award {
+    pub points: i32,
+    pub back_to_back: bool,
+    // Consecutive clearing pieces after the first, zero when not in a combo
+    pub combo: usize,
+}
+
+// Guideline scoring, keeping track of back-to-back chains and combos
+pub struct Scoring {
+    back_to_back: bool,
+    // Clearing pieces in a row, zero after a piece that clears nothing
+    streak: usize,
+}
+
+impl Scoring {
+    pub fn new() -> Scoring {
+        Scoring {
+            back_to_back: false,
+            streak: 0,
+        }
+    }
+
+    // Whether the next difficult clear gets the back-to-back bonus
+    pub fn back_to_back(&self) -> bool {
+        self.back_to_back
+    }
+
+    pub fn combo(&self) -> usize {
+        self.streak.saturating_sub(1)
+    }
+
+    pub fn award(&mut self, clear: &Clear, level: i32) -> Award {
+        let lines = clear.lines.min(4);
+        let row = match clear.spin {
+            Spin::None => 0,
+            Spin::Mini => 1,
+            Spin::Full => 2,
+        };
+        let mut points = CLEAR_POINTS[row][lines] * level;
+
+        let back_to_back = clear.is_difficult() && self.back_to_back;
+        if back_to_back {
+            points = points * BACK_TO_BACK_NUMERATOR / BACK_TO_BACK_DENOMINATOR;
+        }
+
+        if clear.perfect {
+            points += if lines == 4 && back_to_back {
+                BACK_TO_BACK_PERFECT_TETRIS_POINTS
+            } else {
+                PERFECT_CLEAR_POINTS[lines]
+            } * level;
+        }
+
+        // Pieces that clear nothing break a combo but not a back-to-back chain
+        if lines > 0 {
+            self.streak += 1;
+            self.back_to_back = clear.is_difficult();
+        } else {
+            self.streak = 0;
+        }
+        points += COMBO_POINTS * self.combo() as i32 * level;
+
+        Award {
+            points,
+            back_to_back,
+            combo: self.combo(),
+        }
+    }
+}
+
+impl Default for Scoring {
+    fn default() -> Scoring {
+        Scoring::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clear(lines: usize) -> Clear {
+        Clear { lines, spin: Spin::None, perfect: false }
+    }
+
+    #[test]
+    fn back_to_back_tetris_is_worth_half_again() {
+        let mut scoring = Scoring::new();
+        let first = scoring.award(&clear(4), 2);
+        assert_eq!(first.points, 1600);
+        assert!(!first.back_to_back);
+        scoring.award(&clear(0), 2);
+        let second = scoring.award(&clear(4), 2);
+        assert_eq!(second.points, 2400);
+        assert!(second.back_to_back);
+    }
+
+    #[test]
+    fn chain_survives_empty_locks_but_not_a_single() {
+        let mut scoring = Scoring::new();
+        scoring.award(&clear(4), 1);
+        scoring.award(&clear(0), 1);
+        scoring.award(&clear(0), 1);
+        assert!(scoring.back_to_back());
+        assert!(scoring.award(&clear(4), 1).back_to_back);
+        let single = scoring.award(&clear(1), 1);
+        assert!(!single.back_to_back);
+        assert!(!scoring.back_to_back());
+        scoring.award(&clear(0), 1);
+        let tetris = scoring.award(&clear(4), 1);
+        assert!(!tetris.back_to_back);
+        assert_eq!(tetris.points, 800);
+    }
+
+    #[test]
+    fn combo_adds_points_per_step() {
+        let mut scoring = Scoring::new();
+        let awards: Vec<(i32, usize)> = (0..4)
+            .map(|_| scoring.award(&clear(1), 1))
+            .map(|award| (award.points, award.combo))
+            .collect();
+        assert_eq!(awards, vec![(100, 0), (150, 1), (200, 2), (250, 3)]);
+        let miss = scoring.award(&clear(0), 1);
+        assert_eq!((miss.points, miss.combo), (0, 0));
+        assert_eq!(scoring.award(&clear(2), 3).points, 900);
+    }
+
+    #[test]
+    fn back_to_back_tetris_perfect_clear() {
+        let perfect = Clear { perfect: true, ..clear(4) };
+        let mut scoring = Scoring::new();
+        assert_eq!(scoring.award(&perfect, 1).points, 800 + 2000);
+        scoring.award(&clear(0), 1);
+        assert_eq!(scoring.award(&perfect, 1).points, 1200 + 3200);
+    }
+}
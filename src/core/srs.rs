@@ -0,0 +1,136 @@
+// Super Rotation System wall kick data. Offsets are (x, y) with y pointing up,
+// exactly as they are usually published, and are tried in order until one
+// does not collide.
+
+use super::piece::Tetrimonos;
+
+type Kicks = [(i32, i32); 5];
+
+const JLSTZ_KICKS: [Kicks; 8] = [
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)], // 0 -> R
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],     // R -> 0
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],     // R -> 2
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)], // 2 -> R
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],    // 2 -> L
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],  // L -> 2
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],  // L -> 0
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],    // 0 -> L
+];
+
+const I_KICKS: [Kicks; 8] = [
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],  // 0 -> R
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],  // R -> 0
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],  // R -> 2
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],  // 2 -> R
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],  // 2 -> L
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],  // L -> 2
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],  // L -> 0
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],  // 0 -> L
+];
+
+// 180 degree kicks as used by modern clients, shared by every piece
+const HALF_TURN_KICKS: [[(i32, i32); 6]; 4] = [
+    [(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)],   // 0 -> 2
+    [(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],     // R -> L
+    [(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)], // 2 -> 0
+    [(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)],  // L -> R
+];
+
+const NO_KICKS: [(i32, i32); 1] = [(0, 0)];
+
+// Index into the kick tables for a quarter turn between two states
+fn quarter_turn(from: usize, to: usize) -> Option<usize> {
+    match (from, to) {
+        (0, 1) => Some(0),
+        (1, 0) => Some(1),
+        (1, 2) => Some(2),
+        (2, 1) => Some(3),
+        (2, 3) => Some(4),
+        (3, 2) => Some(5),
+        (3, 0) => Some(6),
+        (0, 3) => Some(7),
+        _ => None
+    }
+}
+
+pub fn kicks(t: Tetrimonos, from: usize, to: usize) -> &'static [(i32, i32)] {
+    match t {
+        Tetrimonos::O | Tetrimonos::BLANK | Tetrimonos::GARBAGE => return &NO_KICKS,
+        _ => (),
+    }
+    if (from + 2) % 4 == to {
+        return &HALF_TURN_KICKS[from];
+    }
+    match (t, quarter_turn(from, to)) {
+        (Tetrimonos::I, Some(i)) => &I_KICKS[i],
+        (_, Some(i)) => &JLSTZ_KICKS[i],
+        (_, None) => &NO_KICKS,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::coord::Coord;
+    use super::super::grid::Grid;
+    use super::super::piece::{Piece, Rotation};
+    use super::*;
+
+    fn board(rows: &[&str]) -> Grid {
+        let mut grid = Grid::new(rows.len(), rows[0].len(), 0);
+        for (row, line) in rows.iter().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                if c == '#' {
+                    grid.place(&[Coord::new(row as i32, col as i32)], Tetrimonos::GARBAGE);
+                }
+            }
+        }
+        grid
+    }
+
+    fn piece_at(grid: &Grid, t: Tetrimonos, state: usize, origin: Coord) -> Piece {
+        let mut piece = Piece::new(grid, t, state, 1);
+        assert!(piece.shift(grid, origin - piece.position()));
+        piece
+    }
+
+    #[test]
+    fn jlstz_kicks_off_wall_and_down() {
+        // R -> 2 tries (0, 0) into the wall, (1, 0) into the block, then
+        // (1, -1), one right and one down
+        let grid = board(&[
+            "....",
+            "....",
+            "..#.",
+            "....",
+            "....",
+            "....",
+        ]);
+        let mut piece = piece_at(&grid, Tetrimonos::T, 1, Coord::new(2, 0));
+        assert!(piece.rotate(&grid, Rotation::Clockwise));
+        assert_eq!((piece.position(), piece.state()), (Coord::new(3, 1), 2));
+        assert_eq!(piece.last_kick(), Some((Rotation::Clockwise, 2)));
+    }
+
+    #[test]
+    fn i_kicks_up_off_floor() {
+        // Lying on the floor, 0 -> R only fits with the last kick, (1, 2)
+        let grid = board(&[
+            "......",
+            "......",
+            "......",
+            "......",
+            "......",
+            "......",
+        ]);
+        let mut piece = piece_at(&grid, Tetrimonos::I, 0, Coord::new(5, 2));
+        assert!(piece.rotate(&grid, Rotation::Clockwise));
+        assert_eq!((piece.position(), piece.state()), (Coord::new(3, 3), 1));
+        assert_eq!(piece.last_kick(), Some((Rotation::Clockwise, 4)));
+        assert_eq!(piece.blocks_at(piece.position()), [
+            Coord::new(2, 4),
+            Coord::new(3, 4),
+            Coord::new(4, 4),
+            Coord::new(5, 4),
+        ]);
+    }
+}
@@ -0,0 +1,21 @@
+use super::tspin::Spin;
+
+// Running totals for the current game
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    // Full T-spins, indexed by the number of lines they cleared
+    pub tspins: [usize; 4],
+    // T-spin minis, indexed by the number of lines they cleared
+    pub tspin_minis: [usize; 3],
+    pub perfect_clears: usize,
+}
+
+impl Stats {
+    pub(crate) fn record_spin(&mut self, spin: Spin, lines: usize) {
+        match spin {
+            Spin::Full => self.tspins[lines.min(3)] += 1,
+            Spin::Mini => self.tspin_minis[lines.min(2)] += 1,
+            Spin::None => (),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use super::coord::Coord;
+use super::grid::Grid;
+use super::piece::{Piece, Rotation, Tetrimonos};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Spin {
+    None,
+    Mini,
+    Full,
+}
+
+// Diagonal neighbours of the T's centre
+const CORNERS: [Coord; 4] = [
+    Coord::new(-1, -1),
+    Coord::new(-1, 1),
+    Coord::new(1, 1),
+    Coord::new(1, -1),
+];
+
+// The two corners on the side the T points towards, indexed by state
+const FRONT_CORNERS: [[usize; 2]; 4] = [[0, 1], [1, 2], [2, 3], [3, 0]];
+
+// The quarter turn kick that lets a T-spin mini count as a full T-spin (the
+// "TST" kick). Half turns have their own table where it is a plain shift.
+const UPGRADE_KICK: usize = 4;
+
+// 3-corner rule: a T that got into place by rotating, with at least three of
+// its corners blocked, spun. It is a full T-spin when both corners it points
+// at are blocked or a quarter turn took the last kick, and a mini otherwise.
+pub fn detect(piece: &Piece, grid: &Grid) -> Spin {
+    if piece.tetrimono() != Tetrimonos::T {
+        return Spin::None;
+    }
+    let upgraded = match piece.last_kick() {
+        Some((Rotation::Half, _)) => false,
+        Some((_, kick)) => kick == UPGRADE_KICK,
+        None => return Spin::None,
+    };
+    let blocked: Vec<bool> = CORNERS.iter()
+        .map(|corner| grid.is_occupied(piece.position() + *corner))
+        .collect();
+    if blocked.iter().filter(|b| **b).count() < 3 {
+        return Spin::None;
+    }
+    let front = FRONT_CORNERS[piece.state()];
+    if (blocked[front[0]] && blocked[front[1]]) || upgraded {
+        Spin::Full
+    } else {
+        Spin::Mini
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `#` for a filled cell, anything else for an empty one
+    fn board(rows: &[&str]) -> Grid {
+        let mut grid = Grid::new(rows.len(), rows[0].len(), 0);
+        for (row, line) in rows.iter().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                if c == '#' {
+                    grid.place(&[Coord::new(row as i32, col as i32)], Tetrimonos::GARBAGE);
+                }
+            }
+        }
+        grid
+    }
+
+    // A T in `state` centred on `centre`, put there without rotating
+    fn t_at(grid: &Grid, state: usize, centre: Coord) -> Piece {
+        let mut piece = Piece::new(grid, Tetrimonos::T, state, 1);
+        assert!(piece.shift(grid, centre - piece.position()));
+        piece
+    }
+
+    #[test]
+    fn t_spin_double_is_full() {
+        let grid = board(&[
+            ".....",
+            ".....",
+            "##...",
+            "#...#",
+            "##.##",
+        ]);
+        let mut piece = t_at(&grid, 1, Coord::new(3, 2));
+        assert!(piece.rotate(&grid, Rotation::Clockwise));
+        assert_eq!((piece.position(), piece.state()), (Coord::new(3, 2), 2));
+        assert_eq!(detect(&piece, &grid), Spin::Full);
+    }
+
+    #[test]
+    fn wall_kick_into_corner_is_mini() {
+        let grid = board(&[
+            "....",
+            "....",
+            "....",
+            ".###",
+        ]);
+        let mut piece = t_at(&grid, 0, Coord::new(2, 1));
+        assert!(piece.rotate(&grid, Rotation::Clockwise));
+        assert_eq!(piece.position(), Coord::new(2, 0));
+        assert_eq!(piece.last_kick(), Some((Rotation::Clockwise, 1)));
+        assert_eq!(detect(&piece, &grid), Spin::Mini);
+    }
+
+    #[test]
+    fn last_quarter_turn_kick_upgrades_mini() {
+        // Only one corner in front is filled, but nothing short of the last
+        // kick fits
+        let grid = board(&[
+            "......",
+            "......",
+            "..#...",
+            "......",
+            ".#.#..",
+            "......",
+            ".#....",
+            "......",
+        ]);
+        let mut piece = t_at(&grid, 0, Coord::new(3, 3));
+        assert!(piece.rotate(&grid, Rotation::Clockwise));
+        assert_eq!(piece.position(), Coord::new(5, 2));
+        assert_eq!(piece.last_kick(), Some((Rotation::Clockwise, UPGRADE_KICK)));
+        assert_eq!(detect(&piece, &grid), Spin::Full);
+    }
+
+    #[test]
+    fn half_turn_kick_does_not_upgrade_mini() {
+        let grid = board(&[
+            ".....",
+            ".....",
+            "...#.",
+            ".....",
+            ".#.#.",
+            ".#...",
+            ".....",
+            ".....",
+        ]);
+        let mut piece = t_at(&grid, 1, Coord::new(5, 2));
+        assert!(piece.rotate(&grid, Rotation::Half));
+        assert_eq!(piece.position(), Coord::new(3, 2));
+        assert_eq!(piece.last_kick(), Some((Rotation::Half, UPGRADE_KICK)));
+        assert_eq!(detect(&piece, &grid), Spin::Mini);
+    }
+
+    #[test]
+    fn moving_after_rotating_is_no_spin() {
+        let grid = board(&[
+            "....",
+            "....",
+            "....",
+            "....",
+            "....",
+            ".###",
+        ]);
+        let mut piece = t_at(&grid, 0, Coord::new(2, 1));
+        assert!(piece.rotate(&grid, Rotation::Clockwise));
+        assert!(piece.shift(&grid, Coord::LEFT));
+        assert_eq!(piece.drop_to_shadow(&grid), 2);
+        assert_eq!((piece.position(), piece.state()), (Coord::new(4, 0), 1));
+        assert_eq!(detect(&piece, &grid), Spin::None);
+        // The same spot spun into
+        let mut piece = t_at(&grid, 0, Coord::new(4, 1));
+        assert!(piece.rotate(&grid, Rotation::Clockwise));
+        assert_eq!(piece.position(), Coord::new(4, 0));
+        assert_eq!(detect(&piece, &grid), Spin::Mini);
+    }
+}
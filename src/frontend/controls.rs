@@ -0,0 +1,60 @@
+use ggez::event::{Button, KeyCode};
+
+use tetris_rs::core::Input;
+
+// A keyboard key or gamepad button, so scenes can treat both alike
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Control {
+    Key(KeyCode),
+    Pad(Button),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MenuAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+}
+
+pub fn menu_action(control: Control) -> Option<MenuAction> {
+    match control {
+        Control::Key(KeyCode::Up) | Control::Pad(Button::DPadUp) => Some(MenuAction::Up),
+        Control::Key(KeyCode::Down) | Control::Pad(Button::DPadDown) => Some(MenuAction::Down),
+        Control::Key(KeyCode::Left) | Control::Pad(Button::DPadLeft) => Some(MenuAction::Left),
+        Control::Key(KeyCode::Right) | Control::Pad(Button::DPadRight) => Some(MenuAction::Right),
+        Control::Key(KeyCode::Return) | Control::Key(KeyCode::Space) |
+        Control::Pad(Button::South) | Control::Pad(Button::Start) => Some(MenuAction::Confirm),
+        Control::Key(KeyCode::Escape) | Control::Key(KeyCode::Back) |
+        Control::Pad(Button::East) => Some(MenuAction::Back),
+        _ => None
+    }
+}
+
+pub fn game_input(control: Control) -> Option<Input> {
+    match control {
+        Control::Key(KeyCode::Left) | Control::Pad(Button::DPadLeft) => Some(Input::Left),
+        Control::Key(KeyCode::Right) | Control::Pad(Button::DPadRight) => Some(Input::Right),
+        Control::Key(KeyCode::Up) | Control::Key(KeyCode::X) |
+        Control::Pad(Button::South) => Some(Input::RotateClockwise),
+        Control::Key(KeyCode::Z) | Control::Key(KeyCode::LControl) |
+        Control::Pad(Button::East) => Some(Input::RotateCounterClockwise),
+        Control::Key(KeyCode::A) | Control::Pad(Button::North) => Some(Input::Rotate180),
+        Control::Key(KeyCode::C) | Control::Key(KeyCode::LShift) |
+        Control::Pad(Button::LeftTrigger) | Control::Pad(Button::RightTrigger) => Some(Input::Hold),
+        Control::Key(KeyCode::Down) | Control::Pad(Button::DPadDown) => Some(Input::SoftDrop),
+        Control::Key(KeyCode::Space) | Control::Pad(Button::DPadUp) => Some(Input::HardDrop),
+        Control::Key(KeyCode::D) | Control::Pad(Button::West) => Some(Input::SonicDrop),
+        _ => None
+    }
+}
+
+pub fn is_pause(control: Control) -> bool {
+    matches!(control, Control::Key(KeyCode::Escape) | Control::Key(KeyCode::P) | Control::Pad(Button::Start))
+}
+
+pub fn is_debug(control: Control) -> bool {
+    matches!(control, Control::Key(KeyCode::F3) | Control::Pad(Button::Select))
+}
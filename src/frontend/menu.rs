@@ -0,0 +1,180 @@
+use ggez::{Context, GameResult};
+
+use super::controls::{menu_action, Control, MenuAction};
+use super::modes::{modes, Mode};
+use super::playing::PlayingScene;
+use super::render::draw_screen;
+use super::scene::{Scene, Settings, Transition};
+
+// A titled list of options navigated with up and down
+pub struct Menu {
+    title: String,
+    options: Vec<String>,
+    selected: usize,
+}
+
+impl Menu {
+    pub fn new(title: &str, options: &[&str]) -> Menu {
+        Menu {
+            title: title.to_string(),
+            options: options.iter().map(|option| option.to_string()).collect(),
+            selected: 0,
+        }
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn set_option(&mut self, i: usize, option: String) {
+        self.options[i] = option;
+    }
+
+    // Moves the selection for up and down, wrapping around at either end
+    pub fn navigate(&mut self, action: MenuAction) {
+        let len = self.options.len();
+        match action {
+            MenuAction::Up => self.selected = (self.selected + len - 1) % len,
+            MenuAction::Down => self.selected = (self.selected + 1) % len,
+            _ => ()
+        }
+    }
+
+    pub fn text(&self) -> String {
+        let mut text = format!("{}\n\n", self.title);
+        for (i, option) in self.options.iter().enumerate() {
+            let marker = if i == self.selected {"> "} else {"  "};
+            text += &format!("{}{}\n", marker, option);
+        }
+        text
+    }
+}
+
+pub struct TitleScene {
+    menu: Menu,
+}
+
+impl TitleScene {
+    pub fn new() -> TitleScene {
+        TitleScene {
+            menu: Menu::new("tetris.rs", &["Play", "Settings", "Quit"]),
+        }
+    }
+}
+
+impl Scene for TitleScene {
+    fn draw(&mut self, ctx: &mut Context, _settings: &Settings) -> GameResult {
+        draw_screen(ctx, self.menu.text())
+    }
+
+    fn press(&mut self, settings: &mut Settings, control: Control) -> Transition {
+        match menu_action(control) {
+            Some(MenuAction::Confirm) => match self.menu.selected() {
+                0 => Transition::Push(Box::new(ModeSelectScene::new(settings))),
+                1 => Transition::Push(Box::new(SettingsScene::new(settings))),
+                _ => Transition::Quit,
+            },
+            Some(MenuAction::Back) => Transition::Quit,
+            Some(action) => {
+                self.menu.navigate(action);
+                Transition::None
+            }
+            None => Transition::None,
+        }
+    }
+}
+
+pub struct ModeSelectScene {
+    modes: Vec<Mode>,
+    menu: Menu,
+}
+
+impl ModeSelectScene {
+    pub fn new(settings: &Settings) -> ModeSelectScene {
+        let modes = modes(settings.custom);
+        let names: Vec<&str> = modes.iter().map(|mode| mode.name).collect();
+        ModeSelectScene {
+            menu: Menu::new("Mode", &names),
+            modes,
+        }
+    }
+}
+
+impl Scene for ModeSelectScene {
+    fn draw(&mut self, ctx: &mut Context, _settings: &Settings) -> GameResult {
+        draw_screen(ctx, self.menu.text())
+    }
+
+    fn press(&mut self, settings: &mut Settings, control: Control) -> Transition {
+        match menu_action(control) {
+            Some(MenuAction::Confirm) => {
+                let mode = self.modes[self.menu.selected()];
+                Transition::Push(Box::new(PlayingScene::new(mode, settings)))
+            }
+            Some(MenuAction::Back) => Transition::Pop(1),
+            Some(action) => {
+                self.menu.navigate(action);
+                Transition::None
+            }
+            None => Transition::None,
+        }
+    }
+}
+
+// How far one press of left or right moves the DAS and ARR settings
+const HANDLING_STEP: u32 = 1;
+const MAX_SOFT_DROP_FACTOR: u32 = 40;
+
+// Adjusts handling with left and right
+pub struct SettingsScene {
+    menu: Menu,
+}
+
+impl SettingsScene {
+    pub fn new(settings: &Settings) -> SettingsScene {
+        let mut scene = SettingsScene {
+            menu: Menu::new("Settings", &["", "", "", "Back"]),
+        };
+        scene.refresh(settings);
+        scene
+    }
+
+    fn refresh(&mut self, settings: &Settings) {
+        let handling = &settings.handling;
+        self.menu.set_option(0, format!("DAS: {} frames", handling.das));
+        self.menu.set_option(1, format!("ARR: {} frames", handling.arr));
+        self.menu.set_option(2, format!("Soft drop: {}x", handling.soft_drop_factor));
+    }
+
+    fn adjust(&mut self, settings: &mut Settings, increase: bool) {
+        let handling = &mut settings.handling;
+        match (self.menu.selected(), increase) {
+            (0, true) => handling.das += HANDLING_STEP,
+            (0, false) => handling.das = handling.das.saturating_sub(HANDLING_STEP),
+            (1, true) => handling.arr += HANDLING_STEP,
+            (1, false) => handling.arr = handling.arr.saturating_sub(HANDLING_STEP),
+            (2, true) => handling.soft_drop_factor = (handling.soft_drop_factor + 1).min(MAX_SOFT_DROP_FACTOR),
+            (2, false) => handling.soft_drop_factor = handling.soft_drop_factor.saturating_sub(1).max(1),
+            _ => ()
+        }
+        self.refresh(settings);
+    }
+}
+
+impl Scene for SettingsScene {
+    fn draw(&mut self, ctx: &mut Context, _settings: &Settings) -> GameResult {
+        draw_screen(ctx, self.menu.text())
+    }
+
+    fn press(&mut self, settings: &mut Settings, control: Control) -> Transition {
+        match menu_action(control) {
+            Some(MenuAction::Left) => self.adjust(settings, false),
+            Some(MenuAction::Right) => self.adjust(settings, true),
+            Some(MenuAction::Confirm) if self.menu.selected() == 3 => return Transition::Pop(1),
+            Some(MenuAction::Back) => return Transition::Pop(1),
+            Some(action) => self.menu.navigate(action),
+            None => (),
+        }
+        Transition::None
+    }
+}
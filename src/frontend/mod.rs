@@ -0,0 +1,9 @@
+//! The ggez frontend: a stack of scenes (menus, the game itself and the
+//! overlays drawn on top of it) driving the event loop.
+
+pub mod controls;
+pub mod menu;
+pub mod modes;
+pub mod playing;
+pub mod render;
+pub mod scene;
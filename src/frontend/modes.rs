@@ -0,0 +1,45 @@
+use tetris_rs::core::{GravityCurve, LockReset, RandomizerKind, Rules};
+
+// A named set of rules to pick from the mode select screen
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Mode {
+    pub name: &'static str,
+    pub rules: Rules,
+}
+
+// `custom` holds the rules given on the command line
+pub fn modes(custom: Rules) -> Vec<Mode> {
+    let marathon = Rules {
+        gravity: GravityCurve::Guideline,
+        ..Rules::default()
+    };
+    let classic = Rules {
+        randomizer: RandomizerKind::Nes,
+        gravity: GravityCurve::Nes,
+        hold: false,
+        preview: 1,
+        lock_reset: LockReset::Step,
+        // NES deals pieces flat side up
+        spawn_state: 2,
+        line_clear_delay: 20,
+        are: 10,
+        ..Rules::default()
+    };
+    let tgm = Rules {
+        randomizer: RandomizerKind::History4,
+        gravity: GravityCurve::Tgm,
+        hold: false,
+        preview: 1,
+        lock_reset: LockReset::Step,
+        spawn_state: 2,
+        line_clear_delay: 41,
+        are: 30,
+        ..Rules::default()
+    };
+    vec![
+        Mode { name: "Marathon", rules: marathon },
+        Mode { name: "Classic", rules: classic },
+        Mode { name: "TGM", rules: tgm },
+        Mode { name: "Custom", rules: custom },
+    ]
+}
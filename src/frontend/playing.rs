@@ -0,0 +1,359 @@
+use ggez::{Context, GameResult};
+use ggez::graphics;
+use ggez::graphics::Rect;
+
+
+use tetris_rs::core::{Controller, Event, LockReset, Spin, Tetris, TopOut, MOVE_RESET_LIMIT};
+
+use super::controls::{game_input, is_debug, is_pause, menu_action, Control, MenuAction};
+use super::menu::Menu;
+use super::modes::Mode;
+use super::render::{draw_grid, draw_overlay, draw_piece, draw_preview, format_time, generate_color, Colors, Layout, BOARD_AREA, SCREEN_SIZE};
+use super::scene::{Scene, Settings, Transition};
+
+// Frames a clear stays announced for
+const BANNER_FRAMES: u32 = 90;
+
+fn line_name(lines: usize) -> &'static str {
+    match lines {
+        0 => "",
+        1 => " Single",
+        2 => " Double",
+        3 => " Triple",
+        _ => " Quad",
+    }
+}
+
+// What to announce for an engine event, if anything
+fn announcement(event: &Event) -> Option<String> {
+    match event {
+        Event::Clear { clear, award } => {
+            let mut lines = Vec::new();
+            if clear.perfect {
+                lines.push("Perfect Clear".to_string());
+            }
+            if award.back_to_back {
+                lines.push("Back-to-Back".to_string());
+            }
+            match clear.spin {
+                Spin::Full => lines.push(format!("T-Spin{}", line_name(clear.lines))),
+                Spin::Mini => lines.push(format!("T-Spin Mini{}", line_name(clear.lines))),
+                Spin::None if clear.lines == 4 => lines.push("Tetris".to_string()),
+                Spin::None => (),
+            }
+            if award.combo > 0 {
+                lines.push(format!("{} Combo", award.combo));
+            }
+            if lines.is_empty() {
+                None
+            } else {
+                Some(lines.join("\n"))
+            }
+        }
+    }
+}
+
+// Plays one game of the chosen mode: turns held keys into inputs for each
+// engine frame and draws whatever state it ends up in.
+pub struct PlayingScene {
+    tetris: Tetris,
+    mode: Mode,
+    controller: Controller,
+    layout: Layout,
+    // Toggled with F3
+    debug: bool,
+    // Text announcing the last clear and how many more frames to show it
+    banner: Option<(String, u32)>,
+}
+
+impl PlayingScene {
+    pub fn new(mode: Mode, settings: &Settings) -> PlayingScene {
+        let seed = settings.seed.unwrap_or_else(rand::random);
+        let tetris = Tetris::with_rules(seed, mode.rules);
+        let layout = Layout::new(tetris.grid());
+        PlayingScene {
+            tetris,
+            mode,
+            controller: Controller::new(settings.handling),
+            layout,
+            debug: false,
+            banner: None,
+        }
+    }
+
+    fn pause(&mut self) -> Transition {
+        self.tetris.set_paused(true);
+        self.controller.release_all();
+        Transition::Push(Box::new(PausedScene::new(self.mode)))
+    }
+
+    fn draw_hud(&mut self, ctx: &mut Context) -> GameResult {
+        let font = graphics::Font::default();
+        let score_display = format!("Score:\n{}", self.tetris.score());
+        let mut score_text = graphics::Text::new(score_display);
+        score_text.set_font(font, graphics::Scale::uniform(32.0));
+        score_text.set_bounds(ggez::mint::Point2 {x: 160.0, y: 160.0}, graphics::Align::Center);
+        graphics::draw(ctx, &score_text, ggez::graphics::DrawParam::new()
+            .dest(ggez::mint::Point2 {x: 400.0, y: 80.0}).color(graphics::WHITE))?;
+        let level_display = format!("Level:\n{}", self.tetris.level().number);
+        let mut level_text = graphics::Text::new(level_display);
+        level_text.set_font(font, graphics::Scale::uniform(32.0));
+        level_text.set_bounds(ggez::mint::Point2 {x: 160.0, y: 160.0}, graphics::Align::Center);
+        graphics::draw(ctx, &level_text, ggez::graphics::DrawParam::new()
+            .dest(ggez::mint::Point2 {x: 400.0, y: 160.0}).color(graphics::WHITE))?;
+        let randomizer_display = format!("Randomizer:\n{}", self.tetris.rules().randomizer);
+        let mut randomizer_text = graphics::Text::new(randomizer_display);
+        randomizer_text.set_font(font, graphics::Scale::uniform(24.0));
+        randomizer_text.set_bounds(ggez::mint::Point2 {x: 160.0, y: 160.0}, graphics::Align::Center);
+        graphics::draw(ctx, &randomizer_text, ggez::graphics::DrawParam::new()
+            .dest(ggez::mint::Point2 {x: 400.0, y: 240.0}).color(graphics::WHITE))?;
+        if self.debug {
+            self.draw_debug(ctx)?;
+        }
+        // Pieces to come give as much away as the board does
+        if self.tetris.is_paused() {
+            return Ok(());
+        }
+        if self.tetris.rules().hold {
+            self.draw_hold(ctx)?;
+        }
+        if !self.tetris.upcoming().is_empty() {
+            self.draw_next(ctx)?;
+        }
+        if let Some((banner, _)) = &self.banner {
+            let mut banner_text = graphics::Text::new(banner.as_str());
+            banner_text.set_font(font, graphics::Scale::uniform(28.0));
+            // Across the whole HUD, clear of the board however wide it is
+            banner_text.set_bounds(ggez::mint::Point2 {x: SCREEN_SIZE.0 - BOARD_AREA.0, y: 128.0}, graphics::Align::Center);
+            graphics::draw(ctx, &banner_text, ggez::graphics::DrawParam::new()
+                .dest(ggez::mint::Point2 {x: BOARD_AREA.0, y: 512.0}).color(Colors::PURPLE.get_color()))?;
+        }
+        Ok(())
+    }
+
+    fn draw_debug(&mut self, ctx: &mut Context) -> GameResult {
+        let lock_delay = self.tetris.lock_delay();
+        let mut debug_display = format!(
+            "Gravity: {:.3}G ({})\nLock: {}/{} frames\nReset: {}",
+            self.tetris.gravity().g(),
+            self.tetris.rules().gravity,
+            lock_delay.elapsed(),
+            lock_delay.delay(),
+            lock_delay.reset()
+        );
+        if lock_delay.reset() == LockReset::Move {
+            debug_display += &format!("\nMoves: {}/{}", lock_delay.moves(), MOVE_RESET_LIMIT);
+        }
+        let mut debug_text = graphics::Text::new(debug_display);
+        debug_text.set_font(graphics::Font::default(), graphics::Scale::uniform(18.0));
+        graphics::draw(ctx, &debug_text, ggez::graphics::DrawParam::new()
+            .dest(ggez::mint::Point2 {x: 400.0, y: 440.0}).color(graphics::WHITE))?;
+        Ok(())
+    }
+
+    fn draw_next(&mut self, ctx: &mut Context) -> GameResult {
+        let mut next_text = graphics::Text::new("Next");
+        next_text.set_font(graphics::Font::default(), graphics::Scale::uniform(24.0));
+        next_text.set_bounds(ggez::mint::Point2 {x: 96.0, y: 32.0}, graphics::Align::Center);
+        graphics::draw(ctx, &next_text, ggez::graphics::DrawParam::new()
+            .dest(ggez::mint::Point2 {x: 592.0, y: 80.0}).color(graphics::WHITE))?;
+        let state = self.tetris.rules().spawn_state;
+        for (i, t) in self.tetris.upcoming().iter().enumerate() {
+            draw_preview(ctx, *t, state, 608.0, 120.0 + (i as f32) * 48.0, generate_color(*t))?;
+        }
+        Ok(())
+    }
+
+    fn draw_hold(&mut self, ctx: &mut Context) -> GameResult {
+        let mut hold_text = graphics::Text::new("Hold");
+        hold_text.set_font(graphics::Font::default(), graphics::Scale::uniform(24.0));
+        hold_text.set_bounds(ggez::mint::Point2 {x: 160.0, y: 32.0}, graphics::Align::Center);
+        graphics::draw(ctx, &hold_text, ggez::graphics::DrawParam::new()
+            .dest(ggez::mint::Point2 {x: 400.0, y: 320.0}).color(graphics::WHITE))?;
+        let dims = Rect {
+            x: 440.0,
+            y: 352.0,
+            w: 80.0,
+            h: 48.0,
+        };
+        let rect = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::stroke(2.0),
+            dims,
+            graphics::WHITE
+        )?;
+        graphics::draw(ctx, &rect, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+        if let Some(t) = self.tetris.held() {
+            // Grey the piece out until the current one locks
+            let color =
+                if self.tetris.can_hold() {generate_color(t)}
+                else {Colors::GREY.get_color()};
+            draw_preview(ctx, t, self.tetris.rules().spawn_state, 448.0, 360.0, color)?;
+        }
+        Ok(())
+    }
+}
+
+impl Scene for PlayingScene {
+    fn update(&mut self, _ctx: &mut Context, _settings: &mut Settings) -> Transition {
+        if self.tetris.is_over() {
+            return Transition::None;
+        }
+        let inputs = self.controller.update(self.tetris.gravity());
+        self.tetris.step(&inputs);
+        self.banner = match self.banner.take() {
+            Some((text, left)) if left > 1 => Some((text, left - 1)),
+            _ => None,
+        };
+        for event in self.tetris.drain_events() {
+            if let Some(text) = announcement(&event) {
+                self.banner = Some((text, BANNER_FRAMES));
+            }
+        }
+        if self.tetris.is_over() {
+            // The board stays frozen under the results
+            return Transition::Push(Box::new(ResultsScene::new(self.mode, &self.tetris)));
+        }
+        Transition::None
+    }
+
+    fn draw(&mut self, ctx: &mut Context, _settings: &Settings) -> GameResult {
+        let paused = self.tetris.is_paused();
+        draw_grid(ctx, &self.layout, &self.tetris, paused)?;
+        if !paused {
+            draw_piece(ctx, &self.layout, &self.tetris)?;
+        }
+        self.draw_hud(ctx)
+    }
+
+    fn press(&mut self, _settings: &mut Settings, control: Control) -> Transition {
+        if is_pause(control) {
+            return self.pause();
+        }
+        if is_debug(control) {
+            self.debug = !self.debug;
+        } else if let Some(input) = game_input(control) {
+            self.controller.press(input);
+        }
+        Transition::None
+    }
+
+    fn release(&mut self, control: Control) {
+        if let Some(input) = game_input(control) {
+            self.controller.release(input);
+        }
+    }
+
+    fn focus_lost(&mut self) -> Transition {
+        self.pause()
+    }
+
+    fn resumed(&mut self) {
+        self.tetris.set_paused(false);
+    }
+}
+
+// Shown over a paused game, which hides the board underneath
+pub struct PausedScene {
+    mode: Mode,
+    menu: Menu,
+}
+
+impl PausedScene {
+    pub fn new(mode: Mode) -> PausedScene {
+        PausedScene {
+            mode,
+            menu: Menu::new("Paused", &["Resume", "Restart", "Menu", "Quit"]),
+        }
+    }
+}
+
+impl Scene for PausedScene {
+    fn draw(&mut self, ctx: &mut Context, _settings: &Settings) -> GameResult {
+        draw_overlay(ctx, self.menu.text())
+    }
+
+    fn press(&mut self, settings: &mut Settings, control: Control) -> Transition {
+        if is_pause(control) {
+            return Transition::Pop(1);
+        }
+        match menu_action(control) {
+            Some(MenuAction::Confirm) => match self.menu.selected() {
+                0 => Transition::Pop(1),
+                1 => Transition::Replace(2, Box::new(PlayingScene::new(self.mode, settings))),
+                2 => Transition::Pop(2),
+                _ => Transition::Quit,
+            },
+            Some(MenuAction::Back) => Transition::Pop(1),
+            Some(action) => {
+                self.menu.navigate(action);
+                Transition::None
+            }
+            None => Transition::None,
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+// Final numbers of a finished game, shown over the frozen board
+pub struct ResultsScene {
+    mode: Mode,
+    menu: Menu,
+}
+
+impl ResultsScene {
+    pub fn new(mode: Mode, tetris: &Tetris) -> ResultsScene {
+        let reason = match tetris.top_out() {
+            Some(TopOut::BlockOut) => "Block Out",
+            Some(TopOut::LockOut) => "Lock Out",
+            Some(TopOut::GarbageOut) => "Garbage Out",
+            None => "",
+        };
+        let stats = tetris.stats();
+        let title = format!(
+            "Game Over\n{}\n\nScore: {}\nLines: {}\nLevel: {}\nTime: {}\nT-Spins: {}\nT-Spin Minis: {}\nPerfect Clears: {}\nSeed: {}",
+            reason,
+            tetris.score(),
+            tetris.total_lines(),
+            tetris.level().number,
+            format_time(tetris.time()),
+            stats.tspins.iter().sum::<usize>(),
+            stats.tspin_minis.iter().sum::<usize>(),
+            stats.perfect_clears,
+            tetris.seed()
+        );
+        ResultsScene {
+            mode,
+            menu: Menu::new(&title, &["Restart", "Menu", "Quit"]),
+        }
+    }
+}
+
+impl Scene for ResultsScene {
+    fn draw(&mut self, ctx: &mut Context, _settings: &Settings) -> GameResult {
+        draw_overlay(ctx, self.menu.text())
+    }
+
+    fn press(&mut self, settings: &mut Settings, control: Control) -> Transition {
+        match menu_action(control) {
+            Some(MenuAction::Confirm) => match self.menu.selected() {
+                0 => Transition::Replace(2, Box::new(PlayingScene::new(self.mode, settings))),
+                1 => Transition::Pop(2),
+                _ => Transition::Quit,
+            },
+            Some(MenuAction::Back) => Transition::Pop(2),
+            Some(action) => {
+                self.menu.navigate(action);
+                Transition::None
+            }
+            None => Transition::None,
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,259 @@
+use ggez::{Context, GameResult};
+use ggez::graphics;
+use ggez::graphics::{Color, Rect};
+
+use std::time::Duration;
+
+use tetris_rs::core::{Coord, Grid, Phase, Piece, Tetris, Tetrimonos};
+
+// Largest size of a cell, boards that would not fit get smaller cells
+pub const UNIT: f32 = 32.0;
+
+pub const SCREEN_SIZE: (f32, f32) = (800.0, 640.0);
+
+// The left of the window holds the board, the HUD starts where it ends
+pub const BOARD_AREA: (f32, f32) = (400.0, 640.0);
+
+// How much of the lowest hidden row shows above the board, in cells
+pub const PEEK: f32 = 0.5;
+
+// Where the board sits on screen and how big its cells are
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Layout {
+    pub unit: f32,
+    pub left: f32,
+    // Top of the first visible row
+    pub top: f32,
+    // Hidden rows come first in the grid but are not drawn
+    pub buffer: f32,
+    pub peek: f32,
+}
+
+impl Layout {
+    // Fits the board into `BOARD_AREA` and centres it there
+    pub fn new(grid: &Grid) -> Layout {
+        let peek = if grid.buffer() > 0 {PEEK} else {0.0};
+        let (height, width) = (grid.height() as f32 + peek, grid.width() as f32);
+        let unit = UNIT.min(BOARD_AREA.0 / width).min(BOARD_AREA.1 / height).floor();
+        Layout {
+            unit,
+            left: ((BOARD_AREA.0 - width * unit) / 2.0).floor(),
+            top: ((BOARD_AREA.1 - height * unit) / 2.0).floor() + peek * unit,
+            buffer: grid.buffer() as f32,
+            peek,
+        }
+    }
+
+    // Screen rectangle of a cell, cut off where the buffer stops showing.
+    // Cells hidden entirely have none.
+    pub fn cell(&self, cell: Coord) -> Option<Rect> {
+        let y = self.top + (cell.row as f32 - self.buffer) * self.unit;
+        let limit = self.top - self.peek * self.unit;
+        if y + self.unit <= limit {
+            return None;
+        }
+        Some(Rect {
+            x: self.left + cell.col as f32 * self.unit,
+            y: y.max(limit),
+            w: self.unit,
+            h: y + self.unit - y.max(limit),
+        })
+    }
+}
+
+// Formats as minutes, seconds and hundredths
+pub fn format_time(time: Duration) -> String {
+    let centis = time.as_millis() / 10;
+    format!("{}:{:02}.{:02}", centis / 6000, (centis / 100) % 60, centis % 100)
+}
+
+#[allow(clippy::upper_case_acronyms)]
+pub enum Colors {
+    CYAN,
+    YELLOW,
+    PURPLE,
+    GREEN,
+    RED,
+    BLUE,
+    ORANGE,
+    GREY,
+    BACKGROUND,
+}
+
+impl Colors {
+    pub fn get_color(self) -> Color {
+        match self {
+            Colors::CYAN => Color::from_rgb(115, 218, 255),
+            Colors::YELLOW => Color::from_rgb(255, 255, 54),
+            Colors::PURPLE => Color::from_rgb(134, 54, 255),
+            Colors::GREEN => Color::from_rgb(158, 255, 54),
+            Colors::RED => Color::from_rgb(255, 87, 54),
+            Colors::BLUE => Color::from_rgb(74, 54, 255),
+            Colors::ORANGE => Color::from_rgb(255, 155, 54),
+            Colors::GREY => Color::from_rgb(128, 128, 128),
+            Colors::BACKGROUND => Color::from_rgba(180, 202, 237, 128),
+        }
+    }
+}
+
+pub fn generate_color(t: Tetrimonos) -> Color {
+    match t {
+        Tetrimonos::I => Colors::CYAN.get_color(),
+        Tetrimonos::O => Colors::YELLOW.get_color(),
+        Tetrimonos::T => Colors::PURPLE.get_color(),
+        Tetrimonos::S => Colors::GREEN.get_color(),
+        Tetrimonos::Z => Colors::RED.get_color(),
+        Tetrimonos::J => Colors::BLUE.get_color(),
+        Tetrimonos::L => Colors::ORANGE.get_color(),
+        Tetrimonos::BLANK => Colors::BACKGROUND.get_color(),
+        Tetrimonos::GARBAGE => Colors::GREY.get_color()
+    }
+}
+
+// Draws the piece in play and its shadow, if there is one
+pub fn draw_piece(ctx: &mut Context, layout: &Layout, tetris: &Tetris) -> GameResult<()> {
+    let (piece, shadow) = match (tetris.piece(), tetris.shadow_position()) {
+        (Some(piece), Some(shadow)) => (piece, shadow),
+        _ => return Ok(()),
+    };
+    let color = generate_color(piece.tetrimono());
+    // Draw the real piece
+    for cell in piece.blocks_at(piece.position()).iter() {
+        let dims = match layout.cell(*cell) {
+            Some(dims) => dims,
+            None => continue,
+        };
+        let rect = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            dims,
+            color
+        )?;
+        graphics::draw(ctx, &rect, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+    }
+
+    // Draw the phantom fall position
+    for fall_cell in piece.blocks_at(shadow).iter() {
+        let fall_dims = match layout.cell(*fall_cell) {
+            Some(dims) => dims,
+            None => continue,
+        };
+        let fall_rect = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::stroke(4.0),
+            fall_dims,
+            color
+        )?;
+        graphics::draw(ctx, &fall_rect, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+    }
+    Ok(())
+}
+
+// Draws a tetrimono off the grid in the orientation it spawns in, at half
+// size, with the top left of its bounding box at `(x, y)`
+pub fn draw_preview(ctx: &mut Context, t: Tetrimonos, state: usize, x: f32, y: f32, color: Color) -> GameResult<()> {
+    let unit = UNIT / 2.0;
+    let shape = Piece::shape(t, state);
+    let top = shape.iter().map(|c| c.row).min().unwrap_or(0);
+    let left = shape.iter().map(|c| c.col).min().unwrap_or(0);
+    for cell in shape.iter() {
+        let dims = Rect {
+            x: x + (cell.col - left) as f32 * unit,
+            y: y + (cell.row - top) as f32 * unit,
+            w: unit,
+            h: unit,
+        };
+        let rect = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            dims,
+            color
+        )?;
+        graphics::draw(ctx, &rect, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+    }
+    Ok(())
+}
+
+// Dims the board area and writes `text` over it, centred
+pub fn draw_overlay(ctx: &mut Context, text: String) -> GameResult<()> {
+    let dims = Rect {
+        x: 0.0,
+        y: 0.0,
+        w: BOARD_AREA.0,
+        h: BOARD_AREA.1,
+    };
+    let rect = graphics::Mesh::new_rectangle(
+        ctx,
+        graphics::DrawMode::fill(),
+        dims,
+        Color::from_rgba(0, 0, 0, 200)
+    )?;
+    graphics::draw(ctx, &rect, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+    let mut overlay_text = graphics::Text::new(text);
+    overlay_text.set_font(graphics::Font::default(), graphics::Scale::uniform(28.0));
+    overlay_text.set_bounds(ggez::mint::Point2 {x: dims.w, y: dims.h}, graphics::Align::Center);
+    let top = ((dims.h - overlay_text.height(ctx) as f32) / 2.0).max(0.0).floor();
+    graphics::draw(ctx, &overlay_text, ggez::graphics::DrawParam::new()
+        .dest(ggez::mint::Point2 {x: 0.0, y: top}).color(graphics::WHITE))?;
+    Ok(())
+}
+
+// How much of the line clear delay is left, from 1 down to 0
+fn clear_left(tetris: &Tetris) -> Option<f32> {
+    match tetris.phase() {
+        Phase::LineClear { remaining } => {
+            let delay = tetris.rules().line_clear_delay;
+            Some(if delay > 0 {remaining as f32 / delay as f32} else {0.0})
+        }
+        _ => None,
+    }
+}
+
+// Draws the board, leaving it empty while `hidden` so a paused game
+// cannot be planned ahead. Rows being cleared flash white and collapse.
+pub fn draw_grid(ctx: &mut Context, layout: &Layout, tetris: &Tetris, hidden: bool) -> GameResult<()> {
+    let grid = tetris.grid();
+    let clearing = clear_left(tetris);
+    for i in grid.buffer().saturating_sub(1)..grid.rows() {
+        let collapse = clearing.filter(|_| !hidden && grid.is_row_full(i));
+        for j in 0..grid.width() {
+            let mut dims = match layout.cell(Coord::new(i as i32, j as i32)) {
+                Some(dims) => dims,
+                None => continue,
+            };
+            let cell = if hidden {Tetrimonos::BLANK} else {grid.cell(i, j)};
+            let draw_mode =
+                if cell == Tetrimonos::BLANK
+                {graphics::DrawMode::stroke(3.0)}
+                else {graphics::DrawMode::fill()};
+            let mut color = generate_color(cell);
+            if let Some(left) = collapse {
+                let h = dims.h * left;
+                dims.y += (dims.h - h) / 2.0;
+                dims.h = h;
+                color = graphics::WHITE;
+            }
+            if dims.h <= 0.0 {
+                continue;
+            }
+            let rect = graphics::Mesh::new_rectangle(
+                ctx,
+                draw_mode,
+                dims,
+                color
+            )?;
+            graphics::draw(ctx, &rect, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+        }
+    }
+    Ok(())
+}
+
+// Writes `text` centred on the whole window, for menus
+pub fn draw_screen(ctx: &mut Context, text: String) -> GameResult<()> {
+    let mut screen_text = graphics::Text::new(text);
+    screen_text.set_font(graphics::Font::default(), graphics::Scale::uniform(32.0));
+    screen_text.set_bounds(ggez::mint::Point2 {x: SCREEN_SIZE.0, y: SCREEN_SIZE.1}, graphics::Align::Center);
+    graphics::draw(ctx, &screen_text, ggez::graphics::DrawParam::new()
+        .dest(ggez::mint::Point2 {x: 0.0, y: 160.0}).color(graphics::WHITE))?;
+    Ok(())
+}
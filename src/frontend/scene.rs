@@ -0,0 +1,171 @@
+use ggez::{Context, GameResult};
+use ggez::event::{self, Button, EventHandler, GamepadId, KeyCode, KeyMods};
+use ggez::graphics;
+use ggez::timer;
+
+use tetris_rs::core::{Handling, Rules, FPS};
+
+use super::controls::Control;
+
+// Choices that outlive a single game
+pub struct Settings {
+    pub handling: Handling,
+    // Every game uses this seed when set, otherwise a random one
+    pub seed: Option<u64>,
+    // Rules for the custom mode, taken from the command line
+    pub custom: Rules,
+}
+
+// What the scene stack should do after a scene handled something
+pub enum Transition {
+    None,
+    Push(Box<dyn Scene>),
+    // Removes this many scenes from the top
+    Pop(usize),
+    // Removes this many scenes from the top and puts another in their place
+    Replace(usize, Box<dyn Scene>),
+    Quit,
+}
+
+pub trait Scene {
+    // Advances one frame of `FPS`
+    fn update(&mut self, _ctx: &mut Context, _settings: &mut Settings) -> Transition {
+        Transition::None
+    }
+
+    fn draw(&mut self, ctx: &mut Context, settings: &Settings) -> GameResult;
+
+    fn press(&mut self, settings: &mut Settings, control: Control) -> Transition;
+
+    fn release(&mut self, _control: Control) {}
+
+    fn focus_lost(&mut self) -> Transition {
+        Transition::None
+    }
+
+    // Called when the scene on top of this one goes away
+    fn resumed(&mut self) {}
+
+    // Overlays are drawn on top of the scene underneath them
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+// Drives the ggez event loop, forwarding everything to the top scene
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+    settings: Settings,
+}
+
+impl SceneStack {
+    pub fn new(first: Box<dyn Scene>, settings: Settings) -> SceneStack {
+        SceneStack {
+            scenes: vec![first],
+            settings,
+        }
+    }
+
+    fn apply(&mut self, ctx: &mut Context, transition: Transition) {
+        match transition {
+            Transition::None => return,
+            Transition::Push(scene) => self.scenes.push(scene),
+            Transition::Pop(n) => {
+                let len = self.scenes.len().saturating_sub(n);
+                self.scenes.truncate(len);
+                if let Some(scene) = self.scenes.last_mut() {
+                    scene.resumed();
+                }
+            }
+            Transition::Replace(n, scene) => {
+                let len = self.scenes.len().saturating_sub(n);
+                self.scenes.truncate(len);
+                self.scenes.push(scene);
+            }
+            Transition::Quit => self.scenes.clear(),
+        }
+        if self.scenes.is_empty() {
+            event::quit(ctx);
+        }
+    }
+
+    fn press(&mut self, ctx: &mut Context, control: Control) {
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.press(&mut self.settings, control),
+            None => Transition::None,
+        };
+        self.apply(ctx, transition);
+    }
+
+    fn release(&mut self, control: Control) {
+        if let Some(scene) = self.scenes.last_mut() {
+            scene.release(control);
+        }
+    }
+}
+
+impl EventHandler for SceneStack {
+    // Runs as many fixed frames as fit in the time since the last update,
+    // however fast the machine redraws
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        while timer::check_update_time(ctx, FPS) {
+            let transition = match self.scenes.last_mut() {
+                Some(scene) => scene.update(ctx, &mut self.settings),
+                None => Transition::None,
+            };
+            self.apply(ctx, transition);
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        graphics::clear(ctx, graphics::BLACK);
+        // Start from the topmost scene that is not drawn over another
+        let bottom = self.scenes.iter()
+            .rposition(|scene| !scene.is_overlay())
+            .unwrap_or(0);
+        for scene in self.scenes[bottom..].iter_mut() {
+            scene.draw(ctx, &self.settings)?;
+        }
+        graphics::present(ctx)?;
+        ggez::timer::yield_now();
+        Ok(())
+    }
+
+    fn key_down_event(
+        &mut self,
+        ctx: &mut Context,
+        keycode: KeyCode,
+        _keymod: KeyMods,
+        repeat: bool,
+    ) {
+        // Auto repeat is handled by the game's controller, not the OS
+        if repeat {
+            return;
+        }
+        self.press(ctx, Control::Key(keycode));
+    }
+
+    fn key_up_event(&mut self, _ctx: &mut Context, keycode: KeyCode, _keymod: KeyMods) {
+        self.release(Control::Key(keycode));
+    }
+
+    fn gamepad_button_down_event(&mut self, ctx: &mut Context, btn: Button, _id: GamepadId) {
+        self.press(ctx, Control::Pad(btn));
+    }
+
+    fn gamepad_button_up_event(&mut self, _ctx: &mut Context, btn: Button, _id: GamepadId) {
+        self.release(Control::Pad(btn));
+    }
+
+    fn focus_event(&mut self, ctx: &mut Context, gained: bool) {
+        if gained {
+            return;
+        }
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.focus_lost(),
+            None => Transition::None,
+        };
+        self.apply(ctx, transition);
+    }
+}